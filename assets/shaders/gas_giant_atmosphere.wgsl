@@ -0,0 +1,86 @@
+// Animated gas giant cloud tops
+// Extends the standard PBR material by shifting texture UVs over time:
+// bands drift at latitude-dependent speeds (differential rotation) and slow
+// meanders make storms wobble and evolve as they are carried along.
+
+#import bevy_pbr::{
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::alpha_discard,
+}
+
+#ifdef PREPASS_PIPELINE
+#import bevy_pbr::{
+    prepass_io::{VertexOutput, FragmentOutput},
+    pbr_deferred_functions::deferred_output,
+}
+#else
+#import bevy_pbr::{
+    forward_io::{VertexOutput, FragmentOutput},
+    pbr_functions::{apply_pbr_lighting, main_pass_post_lighting_processing},
+}
+#endif
+
+const PI: f32 = 3.14159265;
+const TAU: f32 = 6.28318531;
+// Must match ATMOSPHERE_BANDS in src/atmosphere
+const BANDS: i32 = 64;
+
+// Phases are wrapped on the CPU in f64, so they stay precise at any warp
+struct AtmosphereSettings {
+    band_phases: array<vec4<f32>, 16>,
+    meander: f32,
+    meander_phase: f32,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(100)
+var<uniform> atmosphere: AtmosphereSettings;
+
+fn band_phase(band: i32) -> f32 {
+    let i = clamp(band, 0, BANDS - 1);
+    return atmosphere.band_phases[i / 4][i % 4];
+}
+
+fn animated_uv(uv: vec2<f32>) -> vec2<f32> {
+    let latitude = (0.5 - uv.y) * PI;
+
+    // Each latitude band has drifted by its own phase (differential rotation
+    // and zonal jets); blend between the two nearest band centers
+    let band = uv.y * f32(BANDS) - 0.5;
+    let lower = floor(band);
+    let phase = band_phase(i32(lower));
+    var delta = band_phase(i32(lower) + 1) - phase;
+    // Phases wrap at 1, so blend the short way around
+    delta -= round(delta);
+
+    var result = uv;
+    result.x = fract(uv.x + phase + delta * (band - lower));
+    // Slow meanders, fading toward the poles where texels are densest
+    result.y += atmosphere.meander * cos(latitude)
+        * sin(result.x * TAU * 3.0 + uv.y * 20.0 + atmosphere.meander_phase);
+    result.y = clamp(result.y, 0.0, 1.0);
+    return result;
+}
+
+@fragment
+fn fragment(
+    vertex_output: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    var in = vertex_output;
+#ifdef VERTEX_UVS_A
+    in.uv = animated_uv(in.uv);
+#endif
+
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+#ifdef PREPASS_PIPELINE
+    let out = deferred_output(in, pbr_input);
+#else
+    var out: FragmentOutput;
+    out.color = apply_pbr_lighting(pbr_input);
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+#endif
+
+    return out;
+}
@@ -0,0 +1,65 @@
+// Point-sprite starfield
+// Every star is a camera-facing quad expanded in view space, sized by angle so
+// stars stay the same size on screen at any distance. Brightness flickers with
+// two beating sine waves per star, and the fragment shader draws a soft disc.
+
+#import bevy_pbr::{
+    mesh_functions,
+    mesh_view_bindings::globals,
+    view_transformations::{position_world_to_view, position_view_to_clip},
+}
+
+struct StarfieldSettings {
+    brightness: f32,
+    twinkle: f32,
+    star_size: f32,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0)
+var<uniform> settings: StarfieldSettings;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) color: vec4<f32>,
+    // corner.xy, size scale, twinkle phase
+    @location(2) star: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec3<f32>,
+    @location(1) corner: vec2<f32>,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+    let world_position = mesh_functions::mesh_position_local_to_world(world_from_local, vec4(vertex.position, 1.0));
+    var view_position = position_world_to_view(world_position.xyz);
+
+    // Half-width of the quad in view space for the star's angular size
+    let half_size = -view_position.z * settings.star_size * vertex.star.z;
+    view_position += vec3(vertex.star.xy * half_size, 0.0);
+
+    // Two incommensurate frequencies per star so the flicker never visibly repeats
+    let phase = vertex.star.w;
+    let speed = 1.5 + fract(phase * 7.31) * 3.0;
+    let flicker = (0.5 + 0.5 * sin(globals.time * speed + phase))
+        * (0.5 + 0.5 * sin(globals.time * speed * 0.37 + phase * 3.1));
+    let twinkle = 1.0 - settings.twinkle * flicker;
+
+    var out: VertexOutput;
+    out.clip_position = position_view_to_clip(view_position);
+    out.color = vertex.color.rgb * settings.brightness * twinkle;
+    out.corner = vertex.star.xy;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Gaussian falloff that reaches nearly zero at the quad edge
+    let falloff = exp(-dot(in.corner, in.corner) * 4.0);
+    // Alpha 0 with premultiplied blending adds the star on top of the sky
+    return vec4(in.color * falloff, 0.0);
+}
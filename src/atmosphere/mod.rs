@@ -0,0 +1,168 @@
+use bevy::{
+    pbr::{ExtendedMaterial, MaterialExtension},
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderType},
+    shader::ShaderRef,
+};
+use serde::{Deserialize, Serialize};
+use crate::simulation::SimulationClock;
+
+/// Shader that animates gas giant cloud tops
+const SHADER_ASSET_PATH: &str = "shaders/gas_giant_atmosphere.wgsl";
+
+/// Latitude bands whose drift is tracked separately; must match the shader
+pub const ATMOSPHERE_BANDS: usize = 64;
+
+/// Angular speed of the meanders, in radians per simulated second
+const MEANDER_RATE: f64 = 0.2;
+
+/// Standard PBR material with animated atmospheric bands
+pub type GasGiantMaterial = ExtendedMaterial<StandardMaterial, AtmosphereExtension>;
+
+/// How a gas giant's clouds move, in texture widths per simulated second
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
+#[serde(default)]
+pub struct AtmosphereDefinition {
+    /// Drift speed of the equatorial band
+    pub equator_speed: f32,
+    /// Drift speed near the poles
+    pub polar_speed: f32,
+    /// Extra speed of alternating jets between bands
+    pub jet_strength: f32,
+    /// Number of alternating jets from pole to pole
+    pub jet_count: f32,
+    /// Amplitude of slow north-south meanders (texture heights)
+    pub meander: f32,
+}
+
+impl Default for AtmosphereDefinition {
+    fn default() -> Self {
+        Self {
+            equator_speed: 0.004,
+            polar_speed: -0.002,
+            jet_strength: 0.0015,
+            jet_count: 8.0,
+            meander: 0.002,
+        }
+    }
+}
+
+impl AtmosphereDefinition {
+    /// Drift speed at a texture row (0 = north pole, 1 = south pole)
+    pub fn speed_at(&self, v: f32) -> f32 {
+        let latitude = (0.5 - v) * std::f32::consts::PI;
+        let s = latitude.sin();
+        // Differential rotation: the equator and poles drift at different rates,
+        // with alternating zonal jets between neighbouring bands
+        self.equator_speed.lerp(self.polar_speed, s * s)
+            + self.jet_strength * (v * self.jet_count * std::f32::consts::PI).sin()
+    }
+}
+
+/// Uniform data for the atmosphere shader
+///
+/// Phases are wrapped on the CPU in f64, since `speed * time` in f32 loses
+/// all precision once warped time gets large.
+#[derive(Debug, Clone, Copy, Default, ShaderType, Reflect)]
+pub struct AtmosphereSettings {
+    /// Horizontal offset of each latitude band in texture widths, in [0, 1),
+    /// packed four to a vector
+    pub band_phases: [Vec4; ATMOSPHERE_BANDS / 4],
+    /// Amplitude of the meanders (texture heights)
+    pub meander: f32,
+    /// Phase of the meanders in radians, in [0, TAU)
+    pub meander_phase: f32,
+}
+
+/// Material extension that drifts the base color texture over time
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone, Default)]
+pub struct AtmosphereExtension {
+    // Bindings 0-99 belong to the base StandardMaterial
+    #[uniform(100)]
+    pub settings: AtmosphereSettings,
+    /// How the clouds move
+    pub definition: AtmosphereDefinition,
+    /// Simulation time the settings were last computed for
+    pub time: f64,
+}
+
+impl AtmosphereExtension {
+    pub fn new(definition: &AtmosphereDefinition) -> Self {
+        let mut extension = Self {
+            settings: AtmosphereSettings {
+                meander: definition.meander,
+                ..default()
+            },
+            definition: *definition,
+            time: 0.0,
+        };
+        extension.set_time(0.0);
+        extension
+    }
+
+    /// Moves the clouds to where they are at a simulation time
+    pub fn set_time(&mut self, time: f64) {
+        for (band, phase) in self.settings.band_phases.iter_mut().flat_map(|v| v.as_mut()).enumerate() {
+            let v = (band as f32 + 0.5) / ATMOSPHERE_BANDS as f32;
+            *phase = (self.definition.speed_at(v) as f64 * time).rem_euclid(1.0) as f32;
+        }
+        self.settings.meander_phase = (MEANDER_RATE * time).rem_euclid(std::f64::consts::TAU) as f32;
+        self.time = time;
+    }
+}
+
+impl MaterialExtension for AtmosphereExtension {
+    fn fragment_shader() -> ShaderRef {
+        SHADER_ASSET_PATH.into()
+    }
+
+    fn deferred_fragment_shader() -> ShaderRef {
+        SHADER_ASSET_PATH.into()
+    }
+}
+
+/// System that moves every gas giant's clouds to the simulation time
+/// Pausing, warping or reversing the clock does the same to the clouds
+pub fn animate_atmospheres(
+    clock: Res<SimulationClock>,
+    mut materials: ResMut<Assets<GasGiantMaterial>>,
+) {
+    // Mutable access re-uploads every material, so skip it while paused
+    if materials.iter().all(|(_, material)| material.extension.time == clock.elapsed) {
+        return;
+    }
+    for (_, material) in materials.iter_mut() {
+        material.extension.set_time(clock.elapsed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phases(extension: &AtmosphereExtension) -> Vec<f32> {
+        extension.settings.band_phases.iter().flat_map(|v| v.to_array()).collect()
+    }
+
+    #[test]
+    fn clouds_keep_moving_smoothly_at_large_times() {
+        let definition = AtmosphereDefinition::default();
+        let mut extension = AtmosphereExtension::new(&definition);
+        // Far beyond where f32 seconds can resolve a 10 second step
+        let time = 1.0e9;
+        extension.set_time(time);
+        let before = phases(&extension);
+        extension.set_time(time + 10.0);
+        let after = phases(&extension);
+
+        for (band, (before, after)) in before.iter().zip(&after).enumerate() {
+            assert!((0.0..1.0).contains(after), "band {band} phase {after}");
+            let v = (band as f32 + 0.5) / ATMOSPHERE_BANDS as f32;
+            let moved = (after - before).rem_euclid(1.0);
+            let expected = (definition.speed_at(v) * 10.0).rem_euclid(1.0);
+            assert!((moved - expected).abs() < 1e-4, "band {band} moved {moved}, expected {expected}");
+        }
+        let meander = extension.settings.meander_phase;
+        assert!((0.0..std::f32::consts::TAU).contains(&meander));
+    }
+}
@@ -0,0 +1,160 @@
+//! Bakes procedural textures to image files without launching the game
+//!
+//! Usage: cargo run --bin bake_textures [output_dir]
+//!
+//! Writes one file per texture (PNG for 8-bit, EXR for float formats) and a
+//! `manifest.ron` recording the generator and parameters behind each, so
+//! generated art can be inspected, diffed, versioned and regenerated. Cubemaps
+//! are written as vertically stacked faces (+X, -X, +Y, -Y, +Z, -Z), the layout
+//! Bevy loads as a cubemap. Baked gas giants can be used in star systems with
+//! `texture: Asset("textures/baked/amber_titan.png")`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
+use image::{ImageBuffer, Rgba};
+use serde::Serialize;
+use my_bevy_game::gas_giant_textures::{self, GasGiantParams, generate_gas_giant_image};
+use my_bevy_game::skybox::{SKYBOX_GENERATOR_VERSION, SkyboxConfig, create_simple_space_skybox};
+use my_bevy_game::texture_cache::TextureCacheKey;
+
+/// Where baked textures go when no directory is given
+const DEFAULT_OUTPUT_DIR: &str = "assets/textures/baked";
+
+/// Parameters a texture was generated from, tagged with its generator
+#[derive(Serialize)]
+enum BakedParams {
+    GasGiant(GasGiantParams),
+    Skybox(SkyboxConfig),
+}
+
+/// Manifest entry describing one baked texture
+#[derive(Serialize)]
+struct BakedTexture {
+    name: String,
+    file: String,
+    generator: String,
+    version: u32,
+    params: BakedParams,
+    /// Same key the runtime texture cache uses for these parameters
+    cache_key: String,
+    width: u32,
+    height: u32,
+    layers: u32,
+}
+
+#[derive(Serialize)]
+struct Manifest {
+    textures: Vec<BakedTexture>,
+}
+
+/// A texture to bake
+struct BakeJob {
+    name: &'static str,
+    generator: &'static str,
+    version: u32,
+    params: BakedParams,
+    key: TextureCacheKey,
+    generate: Box<dyn FnOnce() -> Image>,
+}
+
+fn main() {
+    let output_dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_OUTPUT_DIR));
+    if let Err(error) = bake(&output_dir) {
+        eprintln!("Baking failed: {}", error);
+        std::process::exit(1);
+    }
+}
+
+fn bake(output_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir_all(output_dir)?;
+
+    let mut jobs: Vec<BakeJob> = Vec::new();
+    for (name, params) in [
+        ("amber_titan", GasGiantParams::amber_titan()),
+        ("azure_colossus", GasGiantParams::azure_colossus()),
+    ] {
+        let key = TextureCacheKey::new("gas_giant", gas_giant_textures::GENERATOR_VERSION, &params)
+            .ok_or("gas giant parameters can't be serialized")?;
+        let generator_params = params.clone();
+        jobs.push(BakeJob {
+            name,
+            generator: "gas_giant",
+            version: gas_giant_textures::GENERATOR_VERSION,
+            params: BakedParams::GasGiant(params),
+            key,
+            generate: Box::new(move || generate_gas_giant_image(&generator_params)),
+        });
+    }
+    let skybox = SkyboxConfig::default();
+    let generator_skybox = skybox.clone();
+    jobs.push(BakeJob {
+        name: "skybox",
+        generator: "skybox",
+        version: SKYBOX_GENERATOR_VERSION,
+        key: skybox.cache_key().ok_or("skybox config can't be serialized")?,
+        params: BakedParams::Skybox(skybox),
+        generate: Box::new(move || create_simple_space_skybox(&generator_skybox)),
+    });
+
+    let mut manifest = Manifest { textures: Vec::new() };
+    for job in jobs {
+        println!("Baking {}...", job.name);
+        let image = (job.generate)();
+        let file = write_image(output_dir, job.name, &image)?;
+        let size = image.texture_descriptor.size;
+        manifest.textures.push(BakedTexture {
+            name: job.name.to_string(),
+            file,
+            generator: job.generator.to_string(),
+            version: job.version,
+            params: job.params,
+            cache_key: job.key.id(),
+            width: size.width,
+            height: size.height,
+            layers: size.depth_or_array_layers,
+        });
+    }
+
+    let manifest_path = output_dir.join("manifest.ron");
+    let pretty = ron::ser::PrettyConfig::default();
+    fs::write(&manifest_path, ron::ser::to_string_pretty(&manifest, pretty)?)?;
+    println!("Wrote {} textures and {}", manifest.textures.len(), manifest_path.display());
+    Ok(())
+}
+
+/// Writes an image as PNG or EXR depending on its format, returning the file name
+fn write_image(output_dir: &Path, name: &str, image: &Image) -> Result<String, Box<dyn std::error::Error>> {
+    let data = image.data.clone().ok_or("image has no CPU data")?;
+    let size = image.texture_descriptor.size;
+    // Array layers (cubemap faces) are stored one after another, so stack them vertically
+    let width = size.width;
+    let height = size.height * size.depth_or_array_layers;
+
+    let file = match image.texture_descriptor.format {
+        TextureFormat::Rgba8UnormSrgb | TextureFormat::Rgba8Unorm => {
+            let file = format!("{}.png", name);
+            ImageBuffer::<Rgba<u8>, _>::from_raw(width, height, data)
+                .ok_or("pixel data does not match image size")?
+                .save(output_dir.join(&file))?;
+            file
+        }
+        TextureFormat::Rgba32Float => {
+            let file = format!("{}.exr", name);
+            let pixels = data
+                .chunks_exact(4)
+                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect();
+            ImageBuffer::<Rgba<f32>, Vec<f32>>::from_raw(width, height, pixels)
+                .ok_or("pixel data does not match image size")?
+                .save(output_dir.join(&file))?;
+            file
+        }
+        format => return Err(format!("unsupported texture format {:?}", format).into()),
+    };
+    Ok(file)
+}
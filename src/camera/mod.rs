@@ -1,8 +1,9 @@
 use bevy::{
-    input::mouse::AccumulatedMouseMotion,
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll, MouseScrollUnit},
     prelude::*,
     window::{CursorGrabMode, CursorOptions},
 };
+use crate::entities::{BodyName, BodyOrder, BodyRadius, HomePlanet};
 
 /// Component that marks a camera as a free-fly camera (like spectator mode)
 #[derive(Component)]
@@ -28,17 +29,185 @@ impl Default for FreeFlyCam {
     }
 }
 
-/// System that spawns a 3D camera positioned on the home planet
+/// Component that switches a camera into orbit mode around a body
+/// Free-fly controls are ignored while it's present
+#[derive(Component)]
+pub struct OrbitCam {
+    /// Body being orbited
+    pub target: Entity,
+    /// Angle around the target's vertical axis in radians
+    pub yaw: f32,
+    /// Angle above the target's equatorial plane in radians
+    pub pitch: f32,
+    /// Distance from the target's center
+    pub distance: f32,
+    /// Mouse sensitivity for orbiting
+    pub mouse_sensitivity: f32,
+    /// Fraction of the height above the surface covered per scroll line
+    pub zoom_speed: f32,
+    /// Closest approach, as a fraction of the target's radius above its surface
+    pub surface_margin: f32,
+}
+
+impl OrbitCam {
+    /// Orbit a body of the given radius, framed at a few radii away
+    pub fn new(target: Entity, radius: f32) -> Self {
+        Self {
+            target,
+            yaw: 0.0,
+            pitch: 0.3,
+            distance: radius * 4.0,
+            mouse_sensitivity: 0.003,
+            zoom_speed: 0.15,
+            surface_margin: 0.05,
+        }
+    }
+
+    /// Camera offset from the target's center
+    fn offset(&self) -> Vec3 {
+        self.rotation() * Vec3::Z * self.distance
+    }
+
+    /// Camera rotation looking at the target
+    fn rotation(&self) -> Quat {
+        Quat::from_euler(EulerRot::YXZ, self.yaw, -self.pitch, 0.0)
+    }
+}
+
+/// Component that carries the camera along with a body
+///
+/// The camera's position is kept in the body's frame: whatever the body moves
+/// by each frame is added to the camera, so free-fly movement and looking around
+/// work as usual relative to the body. The frame doesn't spin with the body,
+/// so the sky stays still.
+#[derive(Component)]
+pub struct FollowBody {
+    /// Body whose frame the camera is in
+    pub target: Entity,
+    /// Where the body was when the camera last caught up with it
+    pub anchor: Vec3,
+}
+
+/// Marker for a camera that should start on the home planet once it spawns
+#[derive(Component)]
+pub struct StartOnHomePlanet;
+
+/// Height of the starting viewpoint above the home planet's surface
+const HOME_VIEW_HEIGHT: f32 = 1.5;
+
+/// System that spawns a 3D camera; it's moved onto the home planet once that spawns
 pub fn setup_camera(mut commands: Commands) {
-    // Position camera on the surface of home planet (at orbital radius 18, planet radius 2.5)
-    // Start slightly above the surface looking toward the star
+    // Roughly above the default home planet until the star system has loaded
     commands.spawn((
         Camera3d::default(),
         Transform::from_xyz(18.0, 4.0, 0.0).looking_at(Vec3::ZERO, Vec3::Y),
         FreeFlyCam::default(),
+        StartOnHomePlanet,
     ));
 }
 
+/// Newly spawned home planets
+type NewHomePlanets<'w, 's> =
+    Query<'w, 's, (Entity, &'static Transform, &'static BodyRadius), (Added<HomePlanet>, Without<Camera3d>)>;
+
+/// Cameras that may be moved onto the home planet
+type HomeCameras<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static mut Transform, &'static mut FreeFlyCam, Option<&'static FollowBody>, Has<StartOnHomePlanet>),
+    With<Camera3d>,
+>;
+
+/// System that puts the camera on the home planet and follows it
+///
+/// The first time the home planet spawns, the camera is placed just above it
+/// looking toward the star. When the system is respawned (hot reload), a camera
+/// whose followed body went away moves over to the new home planet, keeping
+/// its offset.
+pub fn follow_home_planet(
+    mut commands: Commands,
+    homes: NewHomePlanets,
+    mut cameras: HomeCameras,
+    bodies: Query<(), With<BodyName>>,
+) {
+    let Some((home, home_transform, radius)) = homes.iter().next() else {
+        return;
+    };
+    let home_position = home_transform.translation;
+
+    for (camera_entity, mut transform, mut free_fly, follow, start) in cameras.iter_mut() {
+        if start {
+            transform.translation = home_position + Vec3::Y * (radius.0 + HOME_VIEW_HEIGHT);
+            transform.look_at(Vec3::ZERO, Vec3::Y);
+            // Match the free-fly angles so the first mouse movement doesn't snap the view
+            let (_, yaw, pitch) = transform.rotation.to_euler(EulerRot::ZYX);
+            free_fly.yaw = yaw;
+            free_fly.pitch = pitch;
+        } else if let Some(follow) = follow.filter(|follow| !bodies.contains(follow.target)) {
+            transform.translation += home_position - follow.anchor;
+        } else {
+            continue;
+        }
+        commands
+            .entity(camera_entity)
+            .remove::<StartOnHomePlanet>()
+            .insert(FollowBody { target: home, anchor: home_position });
+    }
+}
+
+/// Free-fly cameras, with whether they already follow a body
+type FollowToggleCameras<'w, 's> =
+    Query<'w, 's, (Entity, &'static Transform, Has<FollowBody>), (With<Camera3d>, Without<OrbitCam>)>;
+
+/// System that toggles following the nearest body with the B key
+/// The nearest body is the one whose surface is closest to the camera
+pub fn toggle_follow_body(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    bodies: Query<(Entity, &Transform, &BodyRadius), Without<Camera3d>>,
+    cameras: FollowToggleCameras,
+) {
+    if !key_input.just_pressed(KeyCode::KeyB) {
+        return;
+    }
+    for (camera_entity, transform, following) in cameras.iter() {
+        if following {
+            commands.entity(camera_entity).remove::<FollowBody>();
+            continue;
+        }
+        let nearest = bodies.iter().min_by(|(_, a, a_radius), (_, b, b_radius)| {
+            let a_height = a.translation.distance(transform.translation) - a_radius.0;
+            let b_height = b.translation.distance(transform.translation) - b_radius.0;
+            a_height.total_cmp(&b_height)
+        });
+        if let Some((target, target_transform, _)) = nearest {
+            commands.entity(camera_entity).insert(FollowBody {
+                target,
+                anchor: target_transform.translation,
+            });
+        }
+    }
+}
+
+/// Free-fly cameras, with the body they follow
+type FollowingCameras<'w, 's> =
+    Query<'w, 's, (&'static mut FollowBody, &'static mut Transform), (With<Camera3d>, Without<OrbitCam>)>;
+
+/// System that moves following cameras along with their body
+pub fn follow_body(
+    bodies: Query<&Transform, (With<BodyName>, Without<Camera3d>)>,
+    mut cameras: FollowingCameras,
+) {
+    for (mut follow, mut transform) in cameras.iter_mut() {
+        // A despawned body leaves the camera where it is until something retargets it
+        let Ok(body) = bodies.get(follow.target) else {
+            continue;
+        };
+        transform.translation += body.translation - follow.anchor;
+        follow.anchor = body.translation;
+    }
+}
+
 /// System that locks/unlocks the cursor when the window is clicked or Escape is pressed
 pub fn toggle_cursor_lock(
     mut windows: Query<(&Window, &mut CursorOptions)>,
@@ -68,7 +237,7 @@ pub fn toggle_cursor_lock(
 /// System that handles mouse look
 pub fn camera_look(
     accumulated_mouse_motion: Res<AccumulatedMouseMotion>,
-    mut query: Query<(&mut FreeFlyCam, &mut Transform)>,
+    mut query: Query<(&mut FreeFlyCam, &mut Transform), Without<OrbitCam>>,
     windows: Query<(&Window, &CursorOptions)>,
 ) {
     // Only rotate if cursor is locked on a focused window
@@ -97,7 +266,7 @@ pub fn camera_look(
 pub fn camera_movement(
     time: Res<Time>,
     key_input: Res<ButtonInput<KeyCode>>,
-    mut query: Query<(&FreeFlyCam, &mut Transform)>,
+    mut query: Query<(&FreeFlyCam, &mut Transform), Without<OrbitCam>>,
     windows: Query<(&Window, &CursorOptions)>,
 ) {
     // Only move if cursor is locked on a focused window
@@ -143,3 +312,138 @@ pub fn camera_movement(
     }
 }
 
+/// Bodies the orbit camera can target, with their place in the star system
+type OrbitTargets<'w, 's> =
+    Query<'w, 's, (Entity, &'static Transform, &'static BodyRadius, &'static BodyOrder), Without<Camera3d>>;
+
+/// System that picks the body to orbit: F focuses the next body, X returns to free-fly
+/// Bodies are cycled in the order the star system lists them, starting with the star;
+/// the camera keeps its viewing direction on switch.
+/// Returning to free-fly keeps following the body that was orbited (B releases it).
+pub fn select_orbit_target(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    bodies: OrbitTargets,
+    mut cameras: Query<(Entity, &Transform, &mut FreeFlyCam, Option<&OrbitCam>)>,
+) {
+    for (camera_entity, transform, mut free_fly, orbit) in cameras.iter_mut() {
+        // Fall back to free-fly if the target went away (e.g. the system was reloaded)
+        let target_lost = orbit.is_some_and(|orbit| !bodies.contains(orbit.target));
+
+        if key_input.just_pressed(KeyCode::KeyX) || target_lost {
+            if let Some(orbit) = orbit {
+                // Carry the current view over so the camera doesn't snap
+                let (_, yaw, pitch) = transform.rotation.to_euler(EulerRot::ZYX);
+                free_fly.yaw = yaw;
+                free_fly.pitch = pitch.clamp(-std::f32::consts::FRAC_PI_2 + 0.01, std::f32::consts::FRAC_PI_2 - 0.01);
+                let mut camera = commands.entity(camera_entity);
+                camera.remove::<OrbitCam>();
+                // Stay in the orbited body's frame so it doesn't drift out of view
+                if let Ok((_, target_transform, _, _)) = bodies.get(orbit.target) {
+                    camera.insert(FollowBody {
+                        target: orbit.target,
+                        anchor: target_transform.translation,
+                    });
+                }
+            }
+            continue;
+        }
+        if !key_input.just_pressed(KeyCode::KeyF) {
+            continue;
+        }
+
+        let mut targets: Vec<_> = bodies.iter().collect();
+        targets.sort_by_key(|(_, _, _, order)| order.0);
+        let next = match orbit.and_then(|orbit| targets.iter().position(|(entity, ..)| *entity == orbit.target)) {
+            Some(index) => targets.get(index + 1).or(targets.first()),
+            None => targets.first(),
+        };
+        let Some(&(next, target_transform, radius, _)) = next else {
+            continue;
+        };
+
+        // Look at the new target from the side the camera is already on
+        let mut new_orbit = OrbitCam::new(next, radius.0);
+        let direction = (transform.translation - target_transform.translation).normalize_or(Vec3::Z);
+        new_orbit.yaw = direction.x.atan2(direction.z);
+        new_orbit.pitch = direction.y.clamp(-1.0, 1.0).asin();
+        commands.entity(camera_entity).remove::<FollowBody>().insert(new_orbit);
+    }
+}
+
+/// System that orbits the camera around its target
+/// Mouse drags orbit while the cursor is locked, the scroll wheel zooms in steps
+/// proportional to the height above the surface, so zooming slows down near it
+pub fn orbit_camera(
+    accumulated_mouse_motion: Res<AccumulatedMouseMotion>,
+    accumulated_mouse_scroll: Res<AccumulatedMouseScroll>,
+    windows: Query<(&Window, &CursorOptions)>,
+    bodies: Query<(&Transform, &BodyRadius), Without<OrbitCam>>,
+    mut cameras: Query<(&mut OrbitCam, &mut Transform)>,
+) {
+    let cursor_grab = windows.iter().any(|(window, cursor_options)| {
+        window.focused && cursor_options.grab_mode == CursorGrabMode::Locked
+    });
+    let scroll_lines = match accumulated_mouse_scroll.unit {
+        MouseScrollUnit::Line => accumulated_mouse_scroll.delta.y,
+        // Touchpads report pixels; roughly one line per 16 of them
+        MouseScrollUnit::Pixel => accumulated_mouse_scroll.delta.y / 16.0,
+    };
+
+    for (mut orbit, mut transform) in cameras.iter_mut() {
+        // The target follows its orbit every frame, so read where it is now
+        let Ok((target, radius)) = bodies.get(orbit.target) else {
+            continue;
+        };
+
+        if cursor_grab {
+            let delta = accumulated_mouse_motion.delta * orbit.mouse_sensitivity;
+            orbit.yaw -= delta.x;
+            orbit.pitch = (orbit.pitch + delta.y).clamp(-std::f32::consts::FRAC_PI_2 + 0.01, std::f32::consts::FRAC_PI_2 - 0.01);
+        }
+
+        let min_distance = radius.0 * (1.0 + orbit.surface_margin);
+        let height = (orbit.distance - min_distance).max(0.0);
+        orbit.distance = min_distance + height * (1.0 - orbit.zoom_speed).powf(scroll_lines);
+
+        transform.translation = target.translation + orbit.offset();
+        transform.rotation = orbit.rotation();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follow_toggle_only_affects_cameras() {
+        let mut app = App::new();
+        app.init_resource::<ButtonInput<KeyCode>>()
+            .add_systems(Update, toggle_follow_body);
+        let camera = app
+            .world_mut()
+            .spawn((Camera3d::default(), Transform::from_xyz(10.0, 0.0, 0.0)))
+            .id();
+        let body = app
+            .world_mut()
+            .spawn((Transform::from_xyz(12.0, 0.0, 0.0), BodyRadius(1.0), BodyName("Near".into())))
+            .id();
+        let light = app.world_mut().spawn(Transform::from_xyz(11.0, 0.0, 0.0)).id();
+
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::KeyB);
+        app.update();
+
+        let world = app.world();
+        assert_eq!(world.get::<FollowBody>(camera).map(|follow| follow.target), Some(body));
+        assert!(world.get::<FollowBody>(body).is_none());
+        assert!(world.get::<FollowBody>(light).is_none());
+
+        // Pressing B again releases the camera
+        let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        input.release(KeyCode::KeyB);
+        input.clear();
+        input.press(KeyCode::KeyB);
+        app.update();
+        assert!(app.world().get::<FollowBody>(camera).is_none());
+    }
+}
@@ -0,0 +1,255 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    core_pipeline::Skybox,
+    prelude::*,
+    reflect::TypePath,
+};
+use serde::{Deserialize, Serialize};
+use crate::skybox::sky_to_world;
+use crate::star_catalog::equatorial_direction;
+
+/// Bundled constellation figures (relative to `assets/`)
+pub const DEFAULT_CONSTELLATIONS_PATH: &str = "catalogs/constellations.ron";
+
+/// Stick figure of one constellation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstellationDefinition {
+    pub name: String,
+    /// IAU abbreviation, e.g. "Ori"
+    pub abbreviation: String,
+    /// Stars as (right ascension in hours, declination in degrees)
+    pub stars: Vec<(f32, f32)>,
+    /// Lines as pairs of indices into `stars`
+    pub lines: Vec<(usize, usize)>,
+}
+
+impl ConstellationDefinition {
+    /// Sky directions of the stars, in the same frame as `equatorial_direction`
+    pub fn directions(&self) -> impl Iterator<Item = Vec3> + '_ {
+        self.stars.iter().map(|&(hours, degrees)| {
+            equatorial_direction(hours * std::f32::consts::TAU / 24.0, degrees.to_radians())
+        })
+    }
+
+    /// Where the name goes: the average direction of the stars
+    pub fn label_direction(&self) -> Vec3 {
+        self.directions().sum::<Vec3>().normalize_or(Vec3::Y)
+    }
+}
+
+/// Set of constellations loaded from a `.constellations.ron` file
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct ConstellationSet {
+    pub constellations: Vec<ConstellationDefinition>,
+}
+
+/// Loader for `.constellations.ron` files
+#[derive(Default)]
+pub struct ConstellationSetLoader;
+
+impl AssetLoader for ConstellationSetLoader {
+    type Asset = ConstellationSet;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes::<ConstellationSet>(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["constellations.ron"]
+    }
+}
+
+/// Resource holding the constellations shown by the overlay
+#[derive(Resource)]
+pub struct ActiveConstellations(pub Handle<ConstellationSet>);
+
+/// Resource controlling the constellation overlay
+#[derive(Resource)]
+pub struct ConstellationOverlay {
+    /// Whether the overlay is shown (toggled with C)
+    pub visible: bool,
+    /// Current fade, from 0 (hidden) to 1 (fully shown)
+    pub opacity: f32,
+    /// Seconds a full fade in or out takes
+    pub fade_time: f32,
+    /// Distance from the camera the figures are drawn at; must be inside the far plane
+    pub radius: f32,
+    pub line_color: Color,
+    pub label_color: Color,
+}
+
+impl Default for ConstellationOverlay {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            opacity: 0.0,
+            fade_time: 0.6,
+            radius: 900.0,
+            line_color: Color::srgba(0.4, 0.6, 1.0, 0.5),
+            label_color: Color::srgba(0.6, 0.75, 1.0, 0.8),
+        }
+    }
+}
+
+/// Name label of a constellation, kept over its stars on screen
+#[derive(Component)]
+pub struct ConstellationLabel {
+    /// Sky direction the label is pinned to
+    pub direction: Vec3,
+}
+
+/// System that starts loading the bundled constellations
+pub fn load_constellations(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(ActiveConstellations(asset_server.load(DEFAULT_CONSTELLATIONS_PATH)));
+}
+
+/// System that toggles the constellation overlay with the C key
+pub fn toggle_constellations(
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut overlay: ResMut<ConstellationOverlay>,
+) {
+    if key_input.just_pressed(KeyCode::KeyC) {
+        overlay.visible = !overlay.visible;
+    }
+}
+
+/// System that fades the overlay toward its visibility (real time, so it works while paused)
+pub fn fade_constellations(time: Res<Time<Real>>, mut overlay: ResMut<ConstellationOverlay>) {
+    let target = if overlay.visible { 1.0 } else { 0.0 };
+    if overlay.opacity == target {
+        return;
+    }
+    let step = time.delta_secs() / overlay.fade_time.max(1e-3);
+    overlay.opacity = if target > overlay.opacity {
+        (overlay.opacity + step).min(target)
+    } else {
+        (overlay.opacity - step).max(target)
+    };
+}
+
+/// System that (re)spawns the name labels whenever the constellations load or change
+pub fn spawn_constellation_labels(
+    mut commands: Commands,
+    mut events: MessageReader<AssetEvent<ConstellationSet>>,
+    active: Option<Res<ActiveConstellations>>,
+    sets: Res<Assets<ConstellationSet>>,
+    labels: Query<Entity, With<ConstellationLabel>>,
+) {
+    let Some(active) = active else {
+        return;
+    };
+    let reload = events.read().any(|event| match *event {
+        AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => id == active.0.id(),
+        _ => false,
+    });
+    if !reload {
+        return;
+    }
+    let Some(set) = sets.get(&active.0) else {
+        return;
+    };
+
+    for entity in labels.iter() {
+        commands.entity(entity).despawn();
+    }
+    for constellation in &set.constellations {
+        commands.spawn((
+            Text::new(constellation.name.clone()),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::NONE),
+            Node {
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            Visibility::Hidden,
+            ConstellationLabel {
+                direction: constellation.label_direction(),
+            },
+        ));
+    }
+    info!("Loaded {} constellations", set.constellations.len());
+}
+
+/// System that draws the constellation lines on the sky
+///
+/// Lines are placed on a sphere around the camera, so they keep still against
+/// the skybox as the camera moves and turn with it like the skybox does.
+pub fn draw_constellation_lines(
+    mut gizmos: Gizmos,
+    overlay: Res<ConstellationOverlay>,
+    active: Option<Res<ActiveConstellations>>,
+    sets: Res<Assets<ConstellationSet>>,
+    cameras: Query<(&Transform, Option<&Skybox>), With<Camera3d>>,
+) {
+    if overlay.opacity <= 0.0 {
+        return;
+    }
+    let Some(set) = active.and_then(|active| sets.get(&active.0)) else {
+        return;
+    };
+    let Some((camera_transform, skybox)) = cameras.iter().next() else {
+        return;
+    };
+
+    let origin = camera_transform.translation;
+    let to_world = |direction: Vec3| skybox.map_or(direction, |skybox| sky_to_world(skybox, direction));
+    let color = overlay.line_color.with_alpha(overlay.line_color.alpha() * overlay.opacity);
+    for constellation in &set.constellations {
+        let points: Vec<Vec3> = constellation
+            .directions()
+            .map(|direction| origin + to_world(direction) * overlay.radius)
+            .collect();
+        // Straight lines seen from the center of the sphere look like great circle arcs
+        for &(start, end) in &constellation.lines {
+            if let (Some(&start), Some(&end)) = (points.get(start), points.get(end)) {
+                gizmos.line(start, end, color);
+            }
+        }
+    }
+}
+
+/// System that keeps each label over its constellation and fades it with the overlay
+///
+/// Runs before UI layout, which comes before transform propagation, so it reads the
+/// camera's local transform; the camera is a root entity, so that is its global one.
+pub fn position_constellation_labels(
+    overlay: Res<ConstellationOverlay>,
+    cameras: Query<(&Camera, &Transform, Option<&Skybox>), With<Camera3d>>,
+    mut labels: Query<(&ConstellationLabel, &mut Node, &mut TextColor, &mut Visibility)>,
+) {
+    let camera = cameras.iter().next();
+    for (label, mut node, mut text_color, mut visibility) in labels.iter_mut() {
+        let screen_position = camera.filter(|_| overlay.opacity > 0.0).and_then(|(camera, transform, skybox)| {
+            let transform = GlobalTransform::from(*transform);
+            let direction = skybox.map_or(label.direction, |skybox| sky_to_world(skybox, label.direction));
+            // Labels behind the camera would otherwise be mirrored onto the screen
+            if direction.dot(*transform.forward()) <= 0.0 {
+                return None;
+            }
+            camera
+                .world_to_viewport(&transform, transform.translation() + direction * overlay.radius)
+                .ok()
+        });
+
+        let Some(screen_position) = screen_position else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+        node.left = Val::Px(screen_position.x);
+        node.top = Val::Px(screen_position.y);
+        text_color.0 = overlay.label_color.with_alpha(overlay.label_color.alpha() * overlay.opacity);
+    }
+}
@@ -2,6 +2,11 @@ use bevy::{
     diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
     prelude::*,
 };
+use crate::camera::{FollowBody, OrbitCam};
+use crate::entities::BodyName;
+use crate::gravity::NBodySettings;
+use crate::simulation::SimulationClock;
+use crate::texture_tasks::TextureGenerationProgress;
 
 /// Marker component for the debug stats text
 #[derive(Component)]
@@ -34,30 +39,65 @@ pub fn setup_debug_ui(mut commands: Commands) {
 /// System that updates the debug stats display each frame
 pub fn update_debug_stats(
     diagnostics: Res<DiagnosticsStore>,
+    clock: Res<SimulationClock>,
+    nbody: Res<NBodySettings>,
+    texture_progress: Res<TextureGenerationProgress>,
+    cameras: Query<(Option<&OrbitCam>, Option<&FollowBody>), With<Camera3d>>,
+    names: Query<&BodyName>,
     mut query: Query<&mut Text, With<DebugStatsText>>,
 ) {
     for mut text in query.iter_mut() {
         let mut stats_text = String::new();
         
         // FPS (Frames Per Second)
-        if let Some(fps_diagnostic) = diagnostics.get(&FrameTimeDiagnosticsPlugin::FPS) {
-            if let Some(fps_smoothed) = fps_diagnostic.smoothed() {
-                stats_text.push_str(&format!("FPS: {:.1}\n", fps_smoothed));
-            }
+        if let Some(fps_diagnostic) = diagnostics.get(&FrameTimeDiagnosticsPlugin::FPS)
+            && let Some(fps_smoothed) = fps_diagnostic.smoothed()
+        {
+            stats_text.push_str(&format!("FPS: {:.1}\n", fps_smoothed));
         }
         
         // Frame Time (ms)
-        if let Some(frame_time_diagnostic) = diagnostics.get(&FrameTimeDiagnosticsPlugin::FRAME_TIME) {
-            if let Some(frame_time_smoothed) = frame_time_diagnostic.smoothed() {
-                stats_text.push_str(&format!("Frame Time: {:.2}ms\n", frame_time_smoothed));
-            }
+        if let Some(frame_time_diagnostic) = diagnostics.get(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+            && let Some(frame_time_smoothed) = frame_time_diagnostic.smoothed()
+        {
+            stats_text.push_str(&format!("Frame Time: {:.2}ms\n", frame_time_smoothed));
         }
         
         // Entity Count
-        if let Some(entity_count_diagnostic) = diagnostics.get(&bevy::diagnostic::EntityCountDiagnosticsPlugin::ENTITY_COUNT) {
-            if let Some(entity_count) = entity_count_diagnostic.value() {
-                stats_text.push_str(&format!("Entities: {:.0}\n", entity_count));
+        if let Some(entity_count_diagnostic) = diagnostics.get(&bevy::diagnostic::EntityCountDiagnosticsPlugin::ENTITY_COUNT)
+            && let Some(entity_count) = entity_count_diagnostic.value()
+        {
+            stats_text.push_str(&format!("Entities: {:.0}\n", entity_count));
+        }
+        
+        // Simulation clock state
+        let direction = if clock.paused {
+            "paused"
+        } else if clock.reversed {
+            "reverse"
+        } else {
+            "forward"
+        };
+        stats_text.push_str(&format!("Sim Time: {:.0}s ({}x, {})\n", clock.elapsed, clock.warp(), direction));
+        if nbody.lag() > 0.0 {
+            stats_text.push_str(&format!("N-body lag: {:.1}s\n", nbody.lag()));
+        }
+        
+        // Camera mode
+        let name = |entity: Entity| names.get(entity).map(|name| name.0.as_str()).unwrap_or("?");
+        match cameras.iter().next() {
+            Some((Some(orbit), _)) => {
+                stats_text.push_str(&format!("Camera: orbiting {} (F next, X free-fly)\n", name(orbit.target)));
+            }
+            Some((None, Some(follow))) => {
+                stats_text.push_str(&format!("Camera: free-fly with {} (B release, F orbit)\n", name(follow.target)));
             }
+            _ => stats_text.push_str("Camera: free-fly (B follow nearest, F orbit)\n"),
+        }
+
+        // Background texture generation
+        if !texture_progress.is_complete() {
+            stats_text.push_str(&format!("Generating Textures: {:.0}%\n", texture_progress.fraction() * 100.0));
         }
         
         // Memory usage (approximate - Bevy doesn't have built-in memory diagnostics)
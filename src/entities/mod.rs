@@ -1,144 +1,362 @@
+use std::collections::HashMap;
 use bevy::prelude::*;
 use bevy::camera::visibility::NoFrustumCulling;
+use bevy::pbr::ExtendedMaterial;
+use crate::atmosphere::{AtmosphereExtension, GasGiantMaterial};
+use crate::gravity::{Mass, NBodySettings, circular_mean_motion, mean_motion_matches};
+use crate::orbit_paths::OrbitPathColor;
 use crate::orbital::OrbitalBody;
-use crate::gas_giant_textures::{create_amber_titan_texture, create_azure_colossus_texture};
+use crate::rotation::AxialRotation;
+use crate::simulation::SimulationClock;
+use crate::gas_giant_textures::create_gas_giant_texture;
+use crate::star_system::{
+    ActiveStarSystem, BodyDefinition, MaterialDefinition, Rgb, StarSystemDefinition,
+    StarSystemMember, TextureGenerator,
+};
+
+/// Deepest parent chain followed when placing spawned bodies, as in `OrbitResolver`
+const MAX_PARENT_DEPTH: usize = 8;
+
+/// Meridians and latitude rings of gas giant meshes
+const GAS_GIANT_SECTORS: u32 = 128;
+const GAS_GIANT_STACKS: u32 = 64;
 
 /// Marker component for the home planet where camera starts
 #[derive(Component)]
 pub struct HomePlanet;
 
-/// System that spawns the solar system (star and planets)
+/// Display name of a spawned body, as given in its definition
+#[derive(Component)]
+pub struct BodyName(pub String);
+
+/// Visual radius of a spawned body, so cameras can stay outside it
+#[derive(Component)]
+pub struct BodyRadius(pub f32);
+
+/// Place of a spawned body in its star system definition; the star comes first
+#[derive(Component)]
+pub struct BodyOrder(pub usize);
+
+/// System that (re)spawns the solar system whenever its definition loads or changes
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_entities(
     mut commands: Commands,
+    mut events: MessageReader<AssetEvent<StarSystemDefinition>>,
+    active: Option<Res<ActiveStarSystem>>,
+    definitions: Res<Assets<StarSystemDefinition>>,
+    members: Query<Entity, With<StarSystemMember>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    mut gas_giant_materials: ResMut<Assets<GasGiantMaterial>>,
     mut images: ResMut<Assets<Image>>,
+    asset_server: Res<AssetServer>,
+    nbody: Res<NBodySettings>,
+    clock: Res<SimulationClock>,
+) {
+    let Some(active) = active else {
+        events.clear();
+        return;
+    };
+
+    // Generated systems are added directly and never go through the loader
+    let generated = active.0.path().is_none();
+
+    // Read every event so stale ones don't trigger a second respawn next frame
+    let mut changed = false;
+    for event in events.read() {
+        if event.is_loaded_with_dependencies(&active.0)
+            || event.is_modified(&active.0)
+            || (generated && event.is_added(&active.0))
+        {
+            changed = true;
+        }
+    }
+    if !changed {
+        return;
+    }
+    let Some(definition) = definitions.get(&active.0) else {
+        return;
+    };
+
+    // Clear out the previous version of the system before rebuilding it
+    for entity in members.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    info!("Spawning star system \"{}\"", definition.name);
+    spawn_star_system(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        &mut gas_giant_materials,
+        &mut images,
+        &asset_server,
+        definition,
+        nbody.gravitational_constant,
+        clock.elapsed,
+    );
+}
+
+/// Spawns the star and every body of a star system definition
+/// Bodies start where their orbits put them at `time`, so nothing reading
+/// their transforms before the next orbit update sees them out of place
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_star_system(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    gas_giant_materials: &mut Assets<GasGiantMaterial>,
+    images: &mut Assets<Image>,
+    asset_server: &AssetServer,
+    definition: &StarSystemDefinition,
+    gravitational_constant: f32,
+    time: f64,
 ) {
-    info!("Generating gas giant textures...");
-    let amber_texture = create_amber_titan_texture(&mut images);
-    let azure_texture = create_azure_colossus_texture(&mut images);
-    info!("Gas giant textures generated!");
+    let star = definition.star.descriptor;
+
     // Spawn central star (highly emissive, main light source)
     commands.spawn((
-        Mesh3d(meshes.add(Sphere::new(8.0))),
+        Mesh3d(meshes.add(Sphere::new(star.visual_radius()))),
         MeshMaterial3d(materials.add(StandardMaterial {
-            // Much stronger emissive for bright star glow
-            emissive: Color::srgb(50.0, 40.0, 10.0).into(),
-            base_color: Color::srgb(1.0, 0.95, 0.7),
+            emissive: star.emissive(),
+            base_color: star.color(),
             // Unlit appearance - star should glow, not be lit by other sources
             unlit: true,
             ..default()
         })),
         Transform::from_xyz(0.0, 0.0, 0.0),
-        // Strong point light - this should be the primary light source
+        // Point light - this should be the primary light source
         PointLight {
-            intensity: 2_000_000.0,
-            range: 250.0,
-            color: Color::srgb(1.0, 0.95, 0.8),
+            intensity: star.light_intensity(),
+            range: star.light_range(),
+            color: star.color(),
             shadows_enabled: true,
             ..default()
         },
         // Prevent frustum culling so the light stays active even when star is off-screen
         NoFrustumCulling,
+        Mass(star.mass()),
+        star,
+        BodyName(definition.name.clone()),
+        BodyRadius(star.visual_radius()),
+        BodyOrder(0),
+        StarSystemMember,
     ));
 
-    // Planet 1: Home planet (small blue-green, closest orbit) - Cel shaded
-    commands.spawn((
-        Mesh3d(meshes.add(Sphere::new(2.5))),
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color: Color::srgb(0.3, 0.6, 0.95),
-            perceptual_roughness: 1.0,
-            metallic: 0.0,
-            reflectance: 0.0,
-            ..default()
-        })),
-        Transform::from_xyz(18.0, 0.0, 0.0),
-        OrbitalBody::new(18.0, 0.3, 0.0),
-        HomePlanet,
-    ));
+    // Reserve every body first so parents can be referenced regardless of order
+    // Names identify bodies, so a later body reusing one is skipped
+    let mut entities: HashMap<&str, Entity> = HashMap::new();
+    let mut bodies = Vec::with_capacity(definition.bodies.len());
+    for body in &definition.bodies {
+        if entities.contains_key(body.name.as_str()) {
+            warn!("Skipping body \"{}\": another body already has that name", body.name);
+            continue;
+        }
+        entities.insert(body.name.as_str(), commands.spawn(StarSystemMember).id());
+        bodies.push(body);
+    }
 
-    // Planet 2: Rocky red planet (medium orbit) - Cel shaded
-    commands.spawn((
-        Mesh3d(meshes.add(Sphere::new(1.8))),
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color: Color::srgb(0.95, 0.35, 0.25),
-            perceptual_roughness: 1.0,
-            metallic: 0.0,
-            reflectance: 0.0,
-            ..default()
-        })),
-        Transform::from_xyz(28.0, 0.0, 0.0),
-        OrbitalBody::new(28.0, 0.2, std::f32::consts::FRAC_PI_2),
-    ));
+    let orbits: Vec<OrbitalBody> = bodies
+        .iter()
+        .map(|body| {
+            // Moons orbit their parent's mass, everything else the star's
+            let central_mass = body
+                .parent
+                .as_ref()
+                .and_then(|parent| definition.bodies.iter().find(|other| &other.name == parent))
+                .map_or(star.mass(), |parent| parent.mass);
+            let orbit = OrbitalBody::keplerian(
+                body.orbit.semi_major_axis,
+                body.orbit.eccentricity,
+                body.orbit.inclination,
+                body.orbit.longitude_of_ascending_node,
+                body.orbit.argument_of_periapsis,
+                body.orbit.mean_anomaly_at_epoch,
+                mean_motion(body, central_mass, gravitational_constant),
+            );
+            let Some(parent_name) = &body.parent else {
+                return orbit;
+            };
+            match entities.get(parent_name.as_str()) {
+                Some(&parent) => orbit.with_parent(parent),
+                None => {
+                    warn!("Body \"{}\" references unknown parent \"{}\"", body.name, parent_name);
+                    orbit
+                }
+            }
+        })
+        .collect();
 
-    // Planet 3: Gas Giant "Amber Titan" (large cream/tan gas giant with bands)
-    commands.spawn((
-        Mesh3d(meshes.add(
-            Sphere::new(4.5)
-                .mesh()
-                .ico(7)  // High subdivision for smooth surface
-                .unwrap()
-        )),
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color: Color::WHITE,
-            base_color_texture: Some(amber_texture.clone()),
-            // No transmission effects
-            diffuse_transmission: 0.0,
-            specular_transmission: 0.0,
-            thickness: 0.0,
-            ior: 1.0,
-            // Very matte to reduce lighting artifacts
-            perceptual_roughness: 1.0,
-            metallic: 0.0,
-            reflectance: 0.0,
-            // Subtle atmospheric glow
-            emissive: Color::srgb(0.05, 0.04, 0.02).into(),
-            ..default()
-        })),
-        Transform::from_xyz(42.0, 0.0, 0.0),
-        OrbitalBody::new(42.0, 0.12, std::f32::consts::PI),
-    ));
+    // Resolve world positions up front, parents before their moons
+    let lookup: HashMap<Entity, &OrbitalBody> = bodies
+        .iter()
+        .zip(&orbits)
+        .map(|(body, orbit)| (entities[body.name.as_str()], orbit))
+        .collect();
+    let positions: HashMap<Entity, Vec3> = lookup
+        .keys()
+        .map(|&id| (id, spawn_position(id, &lookup, time, 0)))
+        .collect();
 
-    // Planet 4: Small purple planet (medium-far orbit) - Cel shaded
-    commands.spawn((
-        Mesh3d(meshes.add(Sphere::new(1.5))),
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color: Color::srgb(0.75, 0.4, 0.85),
-            perceptual_roughness: 1.0,
-            metallic: 0.0,
-            reflectance: 0.0,
-            ..default()
-        })),
-        Transform::from_xyz(35.0, 0.0, 0.0),
-        OrbitalBody::new(35.0, 0.15, std::f32::consts::FRAC_PI_4 * 3.0),
-    ));
+    // Gas giant textures are expensive, so generate each one once per spawn
+    let mut textures: Vec<(TextureGenerator, Handle<Image>)> = Vec::new();
 
-    // Planet 5: Gas Giant "Azure Colossus" (massive blue-white ice giant)
-    commands.spawn((
-        Mesh3d(meshes.add(
-            Sphere::new(5.5)
+    for (index, (body, orbit)) in bodies.into_iter().zip(orbits).enumerate() {
+        // Gas giants need the animated atmosphere material, so the material type differs
+        let id = entities[body.name.as_str()];
+        match &body.material {
+            MaterialDefinition::Rocky { color, emissive } => {
+                commands.entity(id).insert(MeshMaterial3d(rocky_material(materials, *color, *emissive)));
+            }
+            MaterialDefinition::GasGiant { texture, emissive, atmosphere } => {
+                let texture = match textures.iter().find(|(generator, _)| generator == texture) {
+                    Some((_, handle)) => handle.clone(),
+                    None => {
+                        let handle = match texture {
+                            // Baked images load like any other asset
+                            TextureGenerator::Asset(path) => asset_server.load(path.clone()),
+                            _ => texture.params().map_or_else(Handle::default, |params| {
+                                create_gas_giant_texture(commands, images, &params)
+                            }),
+                        };
+                        textures.push((texture.clone(), handle.clone()));
+                        handle
+                    }
+                };
+                commands.entity(id).insert(MeshMaterial3d(gas_giant_materials.add(ExtendedMaterial {
+                    base: gas_giant_base_material(texture, *emissive),
+                    extension: AtmosphereExtension::new(atmosphere),
+                })));
+            }
+        }
+
+        let mut entity = commands.entity(id);
+        entity.insert((
+            body_mesh(meshes, body),
+            Transform::from_translation(positions[&id]),
+            orbit,
+            Mass(body.mass),
+            BodyName(body.name.clone()),
+            BodyRadius(body.radius),
+            BodyOrder(index + 1),
+        ));
+
+        if let Some(rotation) = &body.rotation {
+            let mut axial = AxialRotation::new(rotation.sidereal_period, rotation.axial_tilt);
+            if rotation.tidally_locked {
+                axial = axial.with_tidal_lock();
+            }
+            entity.insert(axial);
+        }
+        if let Some(color) = body.orbit_color {
+            entity.insert(OrbitPathColor(rgb(color)));
+        }
+        if body.home {
+            entity.insert(HomePlanet);
+        }
+        if let Some(rings) = &body.rings {
+            // Child of the body so it inherits the axial tilt
+            entity.with_child((
+                Mesh3d(meshes.add(Annulus::new(rings.inner_radius, rings.outer_radius))),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: rgb(rings.color).with_alpha(rings.opacity),
+                    alpha_mode: AlphaMode::Blend,
+                    perceptual_roughness: 1.0,
+                    metallic: 0.0,
+                    reflectance: 0.0,
+                    // Visible from above and below
+                    cull_mode: None,
+                    double_sided: true,
+                    ..default()
+                })),
+                // Annulus meshes face +Z; lay it flat in the equatorial plane
+                Transform::from_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)),
+            ));
+        }
+    }
+}
+
+/// World position of a body at a given time, matching what `update_orbits` computes
+/// The star sits at the origin, and so does anything past a cyclic parent chain
+fn spawn_position(id: Entity, orbits: &HashMap<Entity, &OrbitalBody>, time: f64, depth: usize) -> Vec3 {
+    let Some(orbit) = orbits.get(&id) else {
+        return Vec3::ZERO;
+    };
+    if depth > MAX_PARENT_DEPTH {
+        return Vec3::ZERO;
+    }
+    let center = match orbit.parent {
+        Some(parent) => spawn_position(parent, orbits, time, depth + 1),
+        None => orbit.center,
+    };
+    center + orbit.offset_at(time)
+}
+
+/// Mean motion of a body's rail orbit
+/// Derived from the central mass unless given; given values that disagree with
+/// it are kept but warned about, since the body leaves its path in N-body mode
+fn mean_motion(body: &BodyDefinition, central_mass: f32, gravitational_constant: f32) -> f32 {
+    let expected = circular_mean_motion(gravitational_constant, central_mass, body.orbit.semi_major_axis);
+    let Some(mean_motion) = body.orbit.mean_motion else {
+        return expected;
+    };
+    if !mean_motion_matches(mean_motion, expected) {
+        warn!(
+            "Body \"{}\" has mean motion {} but its central mass implies {:.4}; it will change orbit in N-body mode",
+            body.name, mean_motion, expected,
+        );
+    }
+    mean_motion
+}
+
+/// Mesh for a body; gas giants get a UV sphere, whose texture coordinates
+/// follow the equirectangular bands with a straight seam and no pinching
+fn body_mesh(meshes: &mut Assets<Mesh>, body: &BodyDefinition) -> Mesh3d {
+    match body.material {
+        MaterialDefinition::Rocky { .. } => Mesh3d(meshes.add(Sphere::new(body.radius))),
+        MaterialDefinition::GasGiant { .. } => Mesh3d(meshes.add(
+            Sphere::new(body.radius)
                 .mesh()
-                .ico(7)  // High subdivision for smooth surface
-                .unwrap()
+                .uv(GAS_GIANT_SECTORS, GAS_GIANT_STACKS)
+                // UV spheres are built around +Z; spin axes are +Y
+                .rotated_by(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)),
         )),
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color: Color::WHITE,
-            base_color_texture: Some(azure_texture.clone()),
-            // No transmission effects
-            diffuse_transmission: 0.0,
-            specular_transmission: 0.0,
-            thickness: 0.0,
-            ior: 1.0,
-            // Very matte to reduce lighting artifacts
-            perceptual_roughness: 1.0,
-            metallic: 0.0,
-            reflectance: 0.0,
-            // Subtle atmospheric glow
-            emissive: Color::srgb(0.06, 0.08, 0.12).into(),
-            ..default()
-        })),
-        Transform::from_xyz(50.0, 0.0, 0.0),
-        OrbitalBody::new(50.0, 0.08, std::f32::consts::FRAC_PI_4),
-    ));
+    }
+}
+
+/// Cel shaded matte surface for rocky bodies
+fn rocky_material(materials: &mut Assets<StandardMaterial>, color: Rgb, emissive: Rgb) -> Handle<StandardMaterial> {
+    materials.add(StandardMaterial {
+        base_color: rgb(color),
+        perceptual_roughness: 1.0,
+        metallic: 0.0,
+        reflectance: 0.0,
+        emissive: rgb(emissive).into(),
+        ..default()
+    })
 }
 
+/// Base surface for gas giants; the atmosphere extension animates its texture
+fn gas_giant_base_material(texture: Handle<Image>, emissive: Rgb) -> StandardMaterial {
+    StandardMaterial {
+        base_color: Color::WHITE,
+        base_color_texture: Some(texture),
+        // No transmission effects
+        diffuse_transmission: 0.0,
+        specular_transmission: 0.0,
+        thickness: 0.0,
+        ior: 1.0,
+        // Very matte to reduce lighting artifacts
+        perceptual_roughness: 1.0,
+        metallic: 0.0,
+        reflectance: 0.0,
+        // Subtle atmospheric glow
+        emissive: rgb(emissive).into(),
+        ..default()
+    }
+}
+
+/// Converts a definition color into a Bevy color
+fn rgb((r, g, b): Rgb) -> Color {
+    Color::srgb(r, g, b)
+}
@@ -2,114 +2,210 @@ use bevy::prelude::*;
 use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
 use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use crate::noise::{Perlin3, equirect_direction};
+use crate::texture_cache::{TextureCacheKey, load_or_generate};
+use crate::texture_tasks::spawn_texture_task;
 
-/// Creates a procedural banded texture for a gas giant (like Jupiter)
-/// with horizontal bands and turbulent variations
-pub fn create_amber_titan_texture(images: &mut ResMut<Assets<Image>>) -> Handle<Image> {
-    let width = 2048u32;  // Higher res for detail
-    let height = 1024u32;
-    let mut data = vec![0u8; (width * height * 4) as usize];
-    
-    let mut rng = StdRng::seed_from_u64(12345);
-    
-    // More varied band colors
-    let bands = vec![
-        (0.95, 0.82, 0.55),  // Cream
-        (0.82, 0.65, 0.38),  // Light brown
-        (0.98, 0.88, 0.62),  // Pale yellow
-        (0.75, 0.58, 0.32),  // Medium brown
-        (0.88, 0.72, 0.45),  // Tan
-        (0.68, 0.52, 0.28),  // Dark tan
-        (0.92, 0.78, 0.50),  // Golden
-        (0.72, 0.55, 0.30),  // Brown
-    ];
-    
-    for y in 0..height {
-        let v = y as f32 / height as f32;
-        
-        // Complex banding with multiple frequencies
-        let band_pattern = v * 16.0 + 
-                          (v * 25.0).sin() * 0.3 +  // Large waves
-                          (v * 50.0).sin() * 0.15;  // Small ripples
-        let band_index = (band_pattern as usize) % bands.len();
-        
-        for x in 0..width {
-            let u = x as f32 / width as f32;
-            
-            // Multi-scale turbulence for storms and details
-            let turb1 = (u * 120.0 + v * 80.0).sin() * (u * 60.0 - v * 90.0).cos() * 0.06;
-            let turb2 = (u * 200.0 + v * 150.0).sin() * (u * 180.0).cos() * 0.03;
-            let turb3 = (u * 350.0 - v * 280.0).sin() * 0.015;
-            
-            // Add storm spots occasionally
-            let storm_x = (u * 8.0).sin() * 0.5 + 0.5;
-            let storm_y = (v * 12.0).cos() * 0.5 + 0.5;
-            let dist = ((u - storm_x).powi(2) + (v - storm_y).powi(2)).sqrt();
-            let storm = if dist < 0.08 { -0.12 } else { 0.0 };
-            
-            let (r, g, b) = bands[band_index];
-            let total_variation = turb1 + turb2 + turb3 + storm + (rng.r#gen::<f32>() * 0.04 - 0.02);
-            
-            let idx = ((y * width + x) * 4) as usize;
-            data[idx] = ((r + total_variation).clamp(0.0, 1.0) * 255.0) as u8;
-            data[idx + 1] = ((g + total_variation).clamp(0.0, 1.0) * 255.0) as u8;
-            data[idx + 2] = ((b + total_variation).clamp(0.0, 1.0) * 255.0) as u8;
-            data[idx + 3] = 255;
+/// Version of the generator, part of the texture cache key
+/// Bump whenever the output changes for the same parameters
+pub const GENERATOR_VERSION: u32 = 1;
+
+/// Frequency of the fine grain noise on the unit sphere
+const GRAIN_FREQUENCY: f32 = 400.0;
+
+/// Parameters for the procedural gas giant texture generator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GasGiantParams {
+    /// Band colors (sRGB), cycled from pole to pole
+    pub palette: Vec<(f32, f32, f32)>,
+    /// Number of bands from pole to pole
+    pub band_count: f32,
+    /// Sine waves bending the band edges by latitude, as (frequency, amplitude) pairs
+    pub band_waves: Vec<(f32, f32)>,
+    /// How far 3D noise pushes band edges, in bands
+    pub band_warp: f32,
+    /// Number of turbulence octaves, each twice the frequency and half the strength
+    pub turbulence_octaves: u32,
+    /// Frequency of the first turbulence octave on the unit sphere
+    pub turbulence_frequency: f32,
+    /// Color variation from turbulence
+    pub turbulence_strength: f32,
+    /// Number of dark storm spots
+    pub storm_count: u32,
+    /// Angular radius of each storm in radians
+    pub storm_radius: f32,
+    /// How much storms darken the clouds
+    pub storm_strength: f32,
+    /// Amplitude of fine grain noise
+    pub grain: f32,
+    /// Random seed for noise and storm placement
+    pub seed: u64,
+    /// Texture width in pixels (height is half of this)
+    pub resolution: u32,
+}
+
+impl GasGiantParams {
+    /// Large cream/tan gas giant with bands and storms (like Jupiter)
+    pub fn amber_titan() -> Self {
+        Self {
+            // More varied band colors
+            palette: vec![
+                (0.95, 0.82, 0.55),  // Cream
+                (0.82, 0.65, 0.38),  // Light brown
+                (0.98, 0.88, 0.62),  // Pale yellow
+                (0.75, 0.58, 0.32),  // Medium brown
+                (0.88, 0.72, 0.45),  // Tan
+                (0.68, 0.52, 0.28),  // Dark tan
+                (0.92, 0.78, 0.50),  // Golden
+                (0.72, 0.55, 0.30),  // Brown
+            ],
+            band_count: 16.0,
+            band_waves: vec![(25.0, 0.3), (50.0, 0.15)],  // Large waves, small ripples
+            band_warp: 0.35,
+            turbulence_octaves: 5,
+            turbulence_frequency: 8.0,
+            turbulence_strength: 0.08,
+            storm_count: 6,
+            storm_radius: 0.12,
+            storm_strength: 0.12,
+            grain: 0.02,
+            seed: 12345,
+            resolution: 2048,  // Higher res for detail
+        }
+    }
+
+    /// Massive blue-white ice giant with subtle bands (like Neptune/Uranus)
+    pub fn azure_colossus() -> Self {
+        Self {
+            // More varied blue tones
+            palette: vec![
+                (0.78, 0.88, 0.98),  // Pale blue
+                (0.45, 0.68, 0.92),  // Medium blue
+                (0.85, 0.92, 1.0),   // Very light blue
+                (0.35, 0.58, 0.88),  // Deep blue
+                (0.65, 0.80, 0.96),  // Sky blue
+                (0.55, 0.72, 0.90),  // Ocean blue
+            ],
+            band_count: 12.0,
+            band_waves: vec![(18.0, 0.4), (35.0, 0.2)],
+            band_warp: 0.25,
+            turbulence_octaves: 4,
+            turbulence_frequency: 6.0,
+            turbulence_strength: 0.06,
+            storm_count: 0,
+            storm_radius: 0.0,
+            storm_strength: 0.0,
+            grain: 0.015,
+            seed: 54321,
+            resolution: 2048,  // Higher res for detail
         }
     }
-    
-    images.add(Image::new(
+}
+
+/// Creates a procedural banded gas giant texture in the background, reusing the disk cache
+/// Until it is ready the planet shows the average color of its palette
+pub fn create_gas_giant_texture(
+    commands: &mut Commands,
+    images: &mut Assets<Image>,
+    params: &GasGiantParams,
+) -> Handle<Image> {
+    let params = params.clone();
+    spawn_texture_task(commands, images, gas_giant_placeholder(&params), move || {
+        let key = TextureCacheKey::new("gas_giant", GENERATOR_VERSION, &params);
+        load_or_generate(key, || generate_gas_giant_image(&params))
+    })
+}
+
+/// Single texel image in the average palette color
+pub fn gas_giant_placeholder(params: &GasGiantParams) -> Image {
+    let count = params.palette.len().max(1) as f32;
+    let (r, g, b) = params
+        .palette
+        .iter()
+        .fold((0.0, 0.0, 0.0), |sum, color| (sum.0 + color.0, sum.1 + color.1, sum.2 + color.2));
+    let to_byte = |channel: f32| ((channel / count).clamp(0.0, 1.0) * 255.0) as u8;
+
+    Image::new_fill(
         Extent3d {
-            width,
-            height,
+            width: 1,
+            height: 1,
             depth_or_array_layers: 1,
         },
         TextureDimension::D2,
-        data,
+        &[to_byte(r), to_byte(g), to_byte(b), 255],
         TextureFormat::Rgba8UnormSrgb,
         Default::default(),
-    ))
+    )
 }
 
-/// Creates a procedural texture for an ice giant (like Neptune/Uranus)
-/// with subtle bands and atmospheric swirls
-pub fn create_azure_colossus_texture(images: &mut ResMut<Assets<Image>>) -> Handle<Image> {
-    let width = 2048u32;  // Higher res for detail
-    let height = 1024u32;
+/// Generates an equirectangular gas giant texture with horizontal bands,
+/// fractal turbulence and storm spots
+///
+/// Every texel is computed from its direction on the unit sphere, so the
+/// texture itself matches up at u=0/1 and its top and bottom rows each hold a
+/// single pole. It is meant for UV sphere meshes; an ico sphere's coordinates
+/// zig-zag along the seam and pinch at the poles.
+pub fn generate_gas_giant_image(params: &GasGiantParams) -> Image {
+    let width = params.resolution.max(2);
+    let height = (width / 2).max(1);
     let mut data = vec![0u8; (width * height * 4) as usize];
-    
-    let mut rng = StdRng::seed_from_u64(54321);
-    
-    // More varied blue tones
-    let bands = vec![
-        (0.78, 0.88, 0.98),  // Pale blue
-        (0.45, 0.68, 0.92),  // Medium blue
-        (0.85, 0.92, 1.0),   // Very light blue
-        (0.35, 0.58, 0.88),  // Deep blue
-        (0.65, 0.80, 0.96),  // Sky blue
-        (0.55, 0.72, 0.90),  // Ocean blue
-    ];
-    
+
+    let mut rng = StdRng::seed_from_u64(params.seed);
+    let turbulence_noise = Perlin3::new(params.seed);
+    let warp_noise = Perlin3::new(params.seed.wrapping_add(1));
+    let grain_noise = Perlin3::new(params.seed.wrapping_add(2));
+
+    // Scatter storms across the mid latitudes
+    let storms: Vec<Vec3> = (0..params.storm_count)
+        .map(|_| equirect_direction(rng.r#gen::<f32>(), rng.gen_range(0.25..0.75)))
+        .collect();
+
+    let palette = if params.palette.is_empty() {
+        vec![(1.0, 1.0, 1.0)]
+    } else {
+        params.palette.clone()
+    };
+
     for y in 0..height {
-        let v = y as f32 / height as f32;
-        
-        // Varied banding with swirls
-        let band_pattern = v * 12.0 + 
-                          (v * 18.0).sin() * 0.4 +
-                          (v * 35.0).cos() * 0.2;
-        let band_index = (band_pattern as usize) % bands.len();
-        
+        // Sample texel centers so neither pole row collapses to a single point
+        let v = (y as f32 + 0.5) / height as f32;
+
+        // Latitude-only banding with multiple frequencies
+        let band_base = v * params.band_count
+            + params
+                .band_waves
+                .iter()
+                .map(|&(frequency, amplitude)| (v * frequency).sin() * amplitude)
+                .sum::<f32>();
+
         for x in 0..width {
-            let u = x as f32 / width as f32;
-            
-            // Atmospheric swirls and details
-            let swirl1 = (u * 100.0 + v * 70.0).sin() * (u * 50.0 - v * 60.0).cos() * 0.05;
-            let swirl2 = (u * 180.0 - v * 120.0).sin() * (u * 90.0).cos() * 0.025;
-            let detail = (u * 300.0 + v * 250.0).sin() * 0.012;
-            
-            let (r, g, b) = bands[band_index];
-            let total_variation = swirl1 + swirl2 + detail + (rng.r#gen::<f32>() * 0.03 - 0.015);
-            
+            let u = (x as f32 + 0.5) / width as f32;
+            let direction = equirect_direction(u, v);
+
+            // Noise bends band edges into swirls
+            let warp = warp_noise.fbm(direction * params.turbulence_frequency * 0.5, 3) * params.band_warp;
+            let band_index = ((band_base + warp).max(0.0) as usize) % palette.len();
+
+            // Multi-scale turbulence for swirls and details
+            let turbulence = turbulence_noise.fbm(direction * params.turbulence_frequency, params.turbulence_octaves)
+                * params.turbulence_strength;
+
+            // Storm spots darken the clouds with a soft edge
+            let storm = storms
+                .iter()
+                .map(|storm| {
+                    let angle = direction.dot(*storm).clamp(-1.0, 1.0).acos();
+                    1.0 - (angle / params.storm_radius.max(1e-4)).clamp(0.0, 1.0)
+                })
+                .fold(0.0, f32::max)
+                * -params.storm_strength;
+
+            let grain = grain_noise.sample(direction * GRAIN_FREQUENCY) * params.grain;
+
+            let (r, g, b) = palette[band_index];
+            let total_variation = turbulence + storm + grain;
+
             let idx = ((y * width + x) * 4) as usize;
             data[idx] = ((r + total_variation).clamp(0.0, 1.0) * 255.0) as u8;
             data[idx + 1] = ((g + total_variation).clamp(0.0, 1.0) * 255.0) as u8;
@@ -117,8 +213,8 @@ pub fn create_azure_colossus_texture(images: &mut ResMut<Assets<Image>>) -> Hand
             data[idx + 3] = 255;
         }
     }
-    
-    images.add(Image::new(
+
+    Image::new(
         Extent3d {
             width,
             height,
@@ -128,6 +224,5 @@ pub fn create_azure_colossus_texture(images: &mut ResMut<Assets<Image>>) -> Hand
         data,
         TextureFormat::Rgba8UnormSrgb,
         Default::default(),
-    ))
+    )
 }
-
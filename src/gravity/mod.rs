@@ -0,0 +1,306 @@
+use bevy::prelude::*;
+use crate::orbital::{OrbitResolver, OrbitalBody};
+use crate::simulation::SimulationClock;
+
+/// Half-width (simulated seconds) of the finite difference used to seed velocities
+const VELOCITY_SAMPLE_DT: f64 = 0.01;
+
+/// Relative mismatch between a rail orbit's mean motion and its central mass
+/// above which the orbit won't survive switching to N-body mode
+const MEAN_MOTION_TOLERANCE: f32 = 0.03;
+
+/// Gravitational mass of a body
+/// Rail orbits get their mean motion from it (see `circular_mean_motion`), so
+/// switching to N-body mode keeps them on the same path
+#[derive(Component)]
+pub struct Mass(pub f32);
+
+/// Mean motion of an orbit from Kepler's third law (n^2 * a^3 = G * M)
+pub fn circular_mean_motion(gravitational_constant: f32, central_mass: f32, semi_major_axis: f32) -> f32 {
+    (gravitational_constant * central_mass / semi_major_axis.powi(3)).sqrt()
+}
+
+/// Whether a given mean motion agrees with Kepler's third law within a few percent
+pub fn mean_motion_matches(mean_motion: f32, expected: f32) -> bool {
+    (mean_motion - expected).abs() <= expected.abs() * MEAN_MOTION_TOLERANCE
+}
+
+/// Velocity of a body that is integrated under mutual gravity instead of
+/// following its `OrbitalBody` rails
+#[derive(Component, Default)]
+pub struct NBodyState {
+    /// Velocity in world units per simulated second
+    pub velocity: Vec3,
+    /// Entity the body orbited on its rails, if any
+    /// Kept so tidally locked bodies go on facing it
+    pub parent: Option<Entity>,
+    /// Point the body orbited on its rails when it had no parent
+    pub center: Vec3,
+}
+
+/// Marker requesting that a body leave its rails and become an N-body
+/// Its velocity is seeded from its current orbit so the handover is seamless
+#[derive(Component)]
+pub struct SwitchToNBody;
+
+/// Resource configuring the N-body integrator
+#[derive(Resource)]
+pub struct NBodySettings {
+    /// Gravitational constant
+    pub gravitational_constant: f32,
+    /// Fixed integration step in simulated seconds
+    pub fixed_timestep: f64,
+    /// Upper bound on steps per frame; time beyond it carries over to later frames
+    pub max_steps_per_frame: u32,
+    /// Fastest time warp while any body is integrated, so a frame's worth of
+    /// simulated time normally fits in `max_steps_per_frame`
+    pub max_warp: f64,
+    /// Softening length that keeps close encounters from blowing up
+    pub softening: f32,
+    /// Simulated time not yet consumed by a fixed step
+    pub accumulator: f64,
+    /// Clock reading at the previous frame
+    pub last_elapsed: Option<f64>,
+}
+
+impl Default for NBodySettings {
+    fn default() -> Self {
+        Self {
+            gravitational_constant: 1.0,
+            fixed_timestep: 1.0 / 120.0,
+            max_steps_per_frame: 2_000,
+            max_warp: 100.0,
+            softening: 0.5,
+            accumulator: 0.0,
+            last_elapsed: None,
+        }
+    }
+}
+
+impl NBodySettings {
+    /// Simulated seconds the integrator is behind the clock
+    /// Less than a step is normal; more means frames are too slow for `max_steps_per_frame`
+    pub fn lag(&self) -> f64 {
+        let lag = self.accumulator.abs();
+        if lag < self.fixed_timestep { 0.0 } else { lag }
+    }
+}
+
+/// System that converts marked rail bodies into N-bodies
+pub fn switch_to_nbody(
+    mut commands: Commands,
+    clock: Res<SimulationClock>,
+    resolver: OrbitResolver,
+    query: Query<(Entity, &OrbitalBody), With<SwitchToNBody>>,
+) {
+    for (entity, orbital) in query.iter() {
+        let t = clock.elapsed;
+        let before = resolver.position_at(entity, t - VELOCITY_SAMPLE_DT);
+        let after = resolver.position_at(entity, t + VELOCITY_SAMPLE_DT);
+
+        // Central difference of the analytic orbit gives the current velocity
+        let velocity = match (before, after) {
+            (Some(before), Some(after)) => (after - before) / (2.0 * VELOCITY_SAMPLE_DT) as f32,
+            _ => Vec3::ZERO,
+        };
+
+        commands
+            .entity(entity)
+            .remove::<(OrbitalBody, SwitchToNBody)>()
+            .insert(NBodyState {
+                velocity,
+                parent: orbital.parent,
+                center: orbital.center,
+            });
+    }
+}
+
+/// System that switches every rail body with a mass to N-body mode (G key)
+pub fn toggle_nbody_mode(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    query: Query<Entity, (With<OrbitalBody>, With<Mass>)>,
+) {
+    if !key_input.just_pressed(KeyCode::KeyG) {
+        return;
+    }
+
+    let mut count = 0;
+    for entity in query.iter() {
+        commands.entity(entity).insert(SwitchToNBody);
+        count += 1;
+    }
+    if count > 0 {
+        info!("Switching {} bodies to N-body gravity", count);
+    }
+}
+
+/// System that slows the clock down to `max_warp` while there are N-bodies
+/// Rail bodies keep reading the clock directly, so they stay in step either way
+pub fn limit_nbody_warp(
+    mut clock: ResMut<SimulationClock>,
+    settings: Res<NBodySettings>,
+    bodies: Query<(), With<NBodyState>>,
+) {
+    if bodies.is_empty() {
+        return;
+    }
+    while clock.warp() > settings.max_warp && clock.warp_index > 0 {
+        clock.decrease_warp();
+    }
+}
+
+/// System that integrates N-bodies with velocity Verlet on a fixed timestep
+///
+/// Time is taken from the simulation clock, so pausing, warping and reversing
+/// behave the same as for rail bodies. Velocity Verlet is time-reversible, so
+/// running the clock backwards retraces the trajectory. Time beyond the step
+/// budget is not dropped but caught up on over the following frames.
+pub fn integrate_nbody(
+    clock: Res<SimulationClock>,
+    mut settings: ResMut<NBodySettings>,
+    sources: Query<(&Mass, &Transform), Without<NBodyState>>,
+    mut bodies: Query<(&Mass, &mut NBodyState, &mut Transform)>,
+) {
+    let previous = settings.last_elapsed.replace(clock.elapsed).unwrap_or(clock.elapsed);
+    settings.accumulator += clock.elapsed - previous;
+
+    if bodies.is_empty() {
+        settings.accumulator = 0.0;
+        return;
+    }
+
+    // Gather state into flat arrays for the inner loop
+    let fixed: Vec<(Vec3, f32)> = sources
+        .iter()
+        .map(|(mass, transform)| (transform.translation, mass.0))
+        .collect();
+    let mut masses = Vec::new();
+    let mut positions = Vec::new();
+    let mut velocities = Vec::new();
+    for (mass, state, transform) in bodies.iter() {
+        masses.push(mass.0);
+        positions.push(transform.translation);
+        velocities.push(state.velocity);
+    }
+
+    let dt = settings.fixed_timestep;
+    let mut steps = 0;
+    while settings.accumulator.abs() >= dt && steps < settings.max_steps_per_frame {
+        let step = dt.copysign(settings.accumulator);
+        verlet_step(&settings, &fixed, &masses, &mut positions, &mut velocities, step as f32);
+        settings.accumulator -= step;
+        steps += 1;
+    }
+
+    for (i, (_, mut state, mut transform)) in bodies.iter_mut().enumerate() {
+        transform.translation = positions[i];
+        state.velocity = velocities[i];
+    }
+}
+
+/// Advances all N-bodies by one kick-drift-kick step
+fn verlet_step(
+    settings: &NBodySettings,
+    fixed: &[(Vec3, f32)],
+    masses: &[f32],
+    positions: &mut [Vec3],
+    velocities: &mut [Vec3],
+    dt: f32,
+) {
+    let accelerations = compute_accelerations(settings, fixed, masses, positions);
+    for i in 0..positions.len() {
+        velocities[i] += accelerations[i] * (dt * 0.5);
+        positions[i] += velocities[i] * dt;
+    }
+
+    let accelerations = compute_accelerations(settings, fixed, masses, positions);
+    for i in 0..positions.len() {
+        velocities[i] += accelerations[i] * (dt * 0.5);
+    }
+}
+
+/// Gravitational acceleration on each N-body from every other massive body
+fn compute_accelerations(
+    settings: &NBodySettings,
+    fixed: &[(Vec3, f32)],
+    masses: &[f32],
+    positions: &[Vec3],
+) -> Vec<Vec3> {
+    let g = settings.gravitational_constant;
+    let softening_sq = settings.softening * settings.softening;
+
+    let pull = |from: Vec3, to: Vec3, mass: f32| {
+        let delta = to - from;
+        let dist_sq = delta.length_squared() + softening_sq;
+        delta * (g * mass / (dist_sq * dist_sq.sqrt()))
+    };
+
+    positions
+        .iter()
+        .enumerate()
+        .map(|(i, &position)| {
+            let mut acceleration = Vec3::ZERO;
+            for (j, (&other, &mass)) in positions.iter().zip(masses).enumerate() {
+                if i != j {
+                    acceleration += pull(position, other, mass);
+                }
+            }
+            for &(other, mass) in fixed {
+                acceleration += pull(position, other, mass);
+            }
+            acceleration
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circular_mean_motion_follows_keplers_third_law() {
+        // n^2 * a^3 = G * M
+        let n = circular_mean_motion(1.0, 900.0, 18.0);
+        assert!((n * n * 18f32.powi(3) - 900.0).abs() < 1e-2);
+        // Four times farther out is eight times slower
+        let far = circular_mean_motion(1.0, 900.0, 72.0);
+        assert!((n / far - 8.0).abs() < 1e-4);
+        // Doubling G * M is the same as doubling either one
+        assert_eq!(circular_mean_motion(2.0, 900.0, 18.0), circular_mean_motion(1.0, 1800.0, 18.0));
+    }
+
+    #[test]
+    fn mean_motion_matches_within_tolerance() {
+        assert!(mean_motion_matches(1.02, 1.0));
+        assert!(mean_motion_matches(-1.02, -1.0));
+        assert!(!mean_motion_matches(1.1, 1.0));
+    }
+
+    #[test]
+    fn verlet_conserves_energy_over_an_orbit() {
+        let settings = NBodySettings::default();
+        let (g, central_mass, radius) = (settings.gravitational_constant, 900.0, 18.0);
+        let fixed = [(Vec3::ZERO, central_mass)];
+        let mut positions = [Vec3::new(radius, 0.0, 0.0)];
+        let mut velocities = [Vec3::new(0.0, 0.0, (g * central_mass / radius).sqrt())];
+
+        // Softened potential, consistent with the softened force
+        let energy = |position: Vec3, velocity: Vec3| {
+            let softened = (position.length_squared() + settings.softening * settings.softening).sqrt();
+            0.5 * velocity.length_squared() - g * central_mass / softened
+        };
+        let start = energy(positions[0], velocities[0]);
+
+        let period = std::f64::consts::TAU / circular_mean_motion(g, central_mass, radius) as f64;
+        let steps = (period / settings.fixed_timestep).round() as usize;
+        for _ in 0..steps {
+            verlet_step(&settings, &fixed, &[1.0], &mut positions, &mut velocities, settings.fixed_timestep as f32);
+        }
+
+        let end = energy(positions[0], velocities[0]);
+        assert!(((end - start) / start).abs() < 1e-4, "energy went from {start} to {end}");
+        // A full period brings the body back around to where it started
+        assert!(positions[0].distance(Vec3::new(radius, 0.0, 0.0)) < 0.5, "ended at {}", positions[0]);
+    }
+}
@@ -1,10 +1,23 @@
+pub mod atmosphere;
 pub mod camera;
+pub mod constellations;
 pub mod debug_ui;
 pub mod entities;
 pub mod gas_giant_textures;
+pub mod gravity;
 pub mod lighting;
+pub mod noise;
+pub mod orbit_paths;
 pub mod orbital;
+pub mod rotation;
 pub mod setup;
+pub mod simulation;
 pub mod skybox;
+pub mod star_catalog;
+pub mod star_system;
 pub mod starfield;
+pub mod stellar;
+pub mod system_generator;
+pub mod texture_cache;
+pub mod texture_tasks;
 
@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+/// Gradient directions for 3D Perlin noise (cube edge midpoints)
+const GRADIENTS: [Vec3; 12] = [
+    Vec3::new(1.0, 1.0, 0.0),
+    Vec3::new(-1.0, 1.0, 0.0),
+    Vec3::new(1.0, -1.0, 0.0),
+    Vec3::new(-1.0, -1.0, 0.0),
+    Vec3::new(1.0, 0.0, 1.0),
+    Vec3::new(-1.0, 0.0, 1.0),
+    Vec3::new(1.0, 0.0, -1.0),
+    Vec3::new(-1.0, 0.0, -1.0),
+    Vec3::new(0.0, 1.0, 1.0),
+    Vec3::new(0.0, -1.0, 1.0),
+    Vec3::new(0.0, 1.0, -1.0),
+    Vec3::new(0.0, -1.0, -1.0),
+];
+
+/// Seeded 3D Perlin noise with fractal (fBm) helpers
+///
+/// Sampling on the unit sphere gives textures that wrap without seams and
+/// without pinching at the poles, unlike noise computed from UV coordinates.
+pub struct Perlin3 {
+    permutation: [u8; 512],
+}
+
+impl Perlin3 {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut table: Vec<u8> = (0..=255).collect();
+        table.shuffle(&mut rng);
+
+        let mut permutation = [0u8; 512];
+        for (i, value) in permutation.iter_mut().enumerate() {
+            *value = table[i & 255];
+        }
+        Self { permutation }
+    }
+
+    /// Single octave of noise, roughly in -1..1
+    pub fn sample(&self, point: Vec3) -> f32 {
+        let cell = point.floor();
+        let local = point - cell;
+        let xi = (cell.x as i32 & 255) as usize;
+        let yi = (cell.y as i32 & 255) as usize;
+        let zi = (cell.z as i32 & 255) as usize;
+
+        // Quintic fade curve for smooth derivatives
+        let fade = local * local * local * (local * (local * 6.0 - 15.0) + 10.0);
+
+        let p = &self.permutation;
+        let gradient = |x: usize, y: usize, z: usize, offset: Vec3| {
+            let hash = p[p[p[x] as usize + y] as usize + z] as usize;
+            GRADIENTS[hash % GRADIENTS.len()].dot(local - offset)
+        };
+
+        let c000 = gradient(xi, yi, zi, Vec3::new(0.0, 0.0, 0.0));
+        let c100 = gradient(xi + 1, yi, zi, Vec3::new(1.0, 0.0, 0.0));
+        let c010 = gradient(xi, yi + 1, zi, Vec3::new(0.0, 1.0, 0.0));
+        let c110 = gradient(xi + 1, yi + 1, zi, Vec3::new(1.0, 1.0, 0.0));
+        let c001 = gradient(xi, yi, zi + 1, Vec3::new(0.0, 0.0, 1.0));
+        let c101 = gradient(xi + 1, yi, zi + 1, Vec3::new(1.0, 0.0, 1.0));
+        let c011 = gradient(xi, yi + 1, zi + 1, Vec3::new(0.0, 1.0, 1.0));
+        let c111 = gradient(xi + 1, yi + 1, zi + 1, Vec3::new(1.0, 1.0, 1.0));
+
+        let x00 = lerp(c000, c100, fade.x);
+        let x10 = lerp(c010, c110, fade.x);
+        let x01 = lerp(c001, c101, fade.x);
+        let x11 = lerp(c011, c111, fade.x);
+        let y0 = lerp(x00, x10, fade.y);
+        let y1 = lerp(x01, x11, fade.y);
+        lerp(y0, y1, fade.z)
+    }
+
+    /// Fractal Brownian motion: octaves of noise, each at double frequency and half amplitude
+    /// Normalized to roughly -1..1
+    pub fn fbm(&self, point: Vec3, octaves: u32) -> f32 {
+        let mut total = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut normalization = 0.0;
+
+        for _ in 0..octaves.max(1) {
+            total += self.sample(point * frequency) * amplitude;
+            normalization += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+
+        total / normalization
+    }
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Unit direction for an equirectangular texel (u across longitude, v from north to south pole)
+pub fn equirect_direction(u: f32, v: f32) -> Vec3 {
+    let longitude = u * std::f32::consts::TAU;
+    let latitude = (0.5 - v) * std::f32::consts::PI;
+    Vec3::new(
+        latitude.cos() * longitude.cos(),
+        latitude.sin(),
+        latitude.cos() * longitude.sin(),
+    )
+}
+
+/// Equirectangular texture coordinates of a unit direction, the inverse of `equirect_direction`
+pub fn equirect_uv(direction: Vec3) -> Vec2 {
+    let longitude = direction.z.atan2(direction.x);
+    let latitude = direction.y.clamp(-1.0, 1.0).asin();
+    Vec2::new(
+        (longitude / std::f32::consts::TAU).rem_euclid(1.0),
+        0.5 - latitude / std::f32::consts::PI,
+    )
+}
@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+use crate::orbital::{OrbitResolver, OrbitalBody};
+use crate::simulation::SimulationClock;
+
+/// Per-body color for its orbit line
+#[derive(Component)]
+pub struct OrbitPathColor(pub Color);
+
+/// Resource controlling how orbit paths are drawn
+#[derive(Resource)]
+pub struct OrbitPathSettings {
+    /// Whether orbit lines are drawn at all (toggled with O)
+    pub visible: bool,
+    /// Number of line segments per orbit
+    pub segments: usize,
+    /// Camera distance at which lines start to fade
+    pub fade_start: f32,
+    /// Camera distance at which lines are fully faded
+    pub fade_end: f32,
+    /// Alpha of the nearest parts of each line
+    pub max_alpha: f32,
+    /// Color for bodies without an `OrbitPathColor`
+    pub default_color: Color,
+}
+
+impl Default for OrbitPathSettings {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            segments: 128,
+            fade_start: 30.0,
+            fade_end: 250.0,
+            max_alpha: 0.6,
+            default_color: Color::srgb(0.6, 0.6, 0.6),
+        }
+    }
+}
+
+/// System that toggles orbit path drawing with the O key
+pub fn toggle_orbit_paths(
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<OrbitPathSettings>,
+) {
+    if key_input.just_pressed(KeyCode::KeyO) {
+        settings.visible = !settings.visible;
+    }
+}
+
+/// System that draws each orbit as a closed gizmo loop
+///
+/// Paths are traced in eccentric anomaly, which needs no Kepler solve and
+/// samples both ends of an ellipse alike (sampling evenly in time would bunch
+/// points up at apoapsis). They are placed around the parent's current
+/// position so moon orbits travel with their planet.
+pub fn draw_orbit_paths(
+    mut gizmos: Gizmos,
+    settings: Res<OrbitPathSettings>,
+    clock: Res<SimulationClock>,
+    resolver: OrbitResolver,
+    bodies: Query<(Entity, &OrbitalBody, Option<&OrbitPathColor>)>,
+    cameras: Query<&GlobalTransform, With<Camera3d>>,
+) {
+    if !settings.visible || settings.segments < 3 {
+        return;
+    }
+
+    let camera_position = cameras
+        .iter()
+        .next()
+        .map(|transform| transform.translation())
+        .unwrap_or(Vec3::ZERO);
+
+    for (entity, orbital, color) in bodies.iter() {
+        let Some(center) = resolver.center_at(entity, clock.elapsed) else {
+            continue;
+        };
+        let base_color = color.map(|c| c.0).unwrap_or(settings.default_color);
+
+        let points = (0..=settings.segments).map(|i| {
+            let eccentric_anomaly = i as f32 / settings.segments as f32 * std::f32::consts::TAU;
+            let point = center + orbital.offset_at_eccentric_anomaly(eccentric_anomaly);
+
+            // Fade out with distance from the camera
+            let distance = point.distance(camera_position);
+            let fade = 1.0
+                - ((distance - settings.fade_start) / (settings.fade_end - settings.fade_start))
+                    .clamp(0.0, 1.0);
+
+            (point, base_color.with_alpha(settings.max_alpha * fade))
+        });
+
+        gizmos.linestrip_gradient(points);
+    }
+}
@@ -1,45 +1,300 @@
-use bevy::prelude::*;
+use bevy::{
+    ecs::{entity::EntityHashMap, system::SystemParam},
+    prelude::*,
+};
+use crate::simulation::SimulationClock;
 
-/// Component for bodies that orbit around a central point
+/// Maximum Newton-Raphson iterations when solving Kepler's equation
+const KEPLER_MAX_ITERATIONS: usize = 16;
+/// Convergence tolerance (radians) for Kepler's equation
+const KEPLER_TOLERANCE: f32 = 1e-6;
+/// Deepest parent chain followed when resolving hierarchical orbits
+const MAX_ORBIT_DEPTH: usize = 8;
+
+/// Component for bodies that follow a Keplerian orbit around a central point
+///
+/// The orbit is described by the classical orbital elements. The reference
+/// plane is the world XZ plane with +Y as its normal, and angles increase from
+/// +X toward +Z (matching the original circular orbits).
 #[derive(Component)]
 pub struct OrbitalBody {
-    /// Distance from the center point
-    pub orbital_radius: f32,
-    /// Speed of rotation in radians per second
-    pub orbital_speed: f32,
-    /// Current angle in radians
-    pub current_angle: f32,
-    /// Center point to orbit around
+    /// Semi-major axis (half the longest diameter of the ellipse)
+    pub semi_major_axis: f32,
+    /// Eccentricity (0 = circle, 0..1 = ellipse)
+    pub eccentricity: f32,
+    /// Tilt of the orbital plane relative to the XZ plane, in radians
+    pub inclination: f32,
+    /// Angle from +X to the ascending node, in radians
+    pub longitude_of_ascending_node: f32,
+    /// Angle from the ascending node to periapsis, in radians
+    pub argument_of_periapsis: f32,
+    /// Mean anomaly at epoch (time zero), in radians
+    pub mean_anomaly_at_epoch: f32,
+    /// Mean motion (average angular speed) in radians per simulated second
+    pub mean_motion: f32,
+    /// Fixed center point to orbit around (the focus of the ellipse)
+    /// Ignored when `parent` is set; the parent's position is used instead
     pub center: Vec3,
+    /// Optional entity to orbit (e.g. a planet for a moon)
+    pub parent: Option<Entity>,
 }
 
 impl OrbitalBody {
+    /// Creates a circular orbit in the XZ plane
     pub fn new(radius: f32, speed: f32, starting_angle: f32) -> Self {
         Self {
-            orbital_radius: radius,
-            orbital_speed: speed,
-            current_angle: starting_angle,
+            semi_major_axis: radius,
+            eccentricity: 0.0,
+            inclination: 0.0,
+            longitude_of_ascending_node: 0.0,
+            argument_of_periapsis: 0.0,
+            mean_anomaly_at_epoch: starting_angle,
+            mean_motion: speed,
+            center: Vec3::ZERO,
+            parent: None,
+        }
+    }
+
+    /// Creates an orbit from the full set of Keplerian elements
+    pub fn keplerian(
+        semi_major_axis: f32,
+        eccentricity: f32,
+        inclination: f32,
+        longitude_of_ascending_node: f32,
+        argument_of_periapsis: f32,
+        mean_anomaly_at_epoch: f32,
+        mean_motion: f32,
+    ) -> Self {
+        Self {
+            semi_major_axis,
+            eccentricity: eccentricity.clamp(0.0, 0.999),
+            inclination,
+            longitude_of_ascending_node,
+            argument_of_periapsis,
+            mean_anomaly_at_epoch,
+            mean_motion,
             center: Vec3::ZERO,
+            parent: None,
+        }
+    }
+
+    /// Makes this body orbit the current position of another entity
+    pub fn with_parent(mut self, parent: Entity) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Rotation taking the perifocal frame (periapsis along +X) into world space
+    pub fn orientation(&self) -> Quat {
+        // Angles in this convention sweep from +X toward +Z, which is a
+        // rotation about -Y. Inclination lifts the +Z side of the line of
+        // nodes toward +Y.
+        Quat::from_rotation_y(-self.longitude_of_ascending_node)
+            * Quat::from_rotation_x(-self.inclination)
+            * Quat::from_rotation_y(-self.argument_of_periapsis)
+    }
+
+    /// Mean anomaly at a given simulation time (seconds since the epoch)
+    pub fn mean_anomaly_at(&self, time: f64) -> f32 {
+        // Computed in f64 so large warped times don't lose precision
+        (self.mean_anomaly_at_epoch as f64 + self.mean_motion as f64 * time)
+            .rem_euclid(std::f64::consts::TAU) as f32
+    }
+
+    /// Offset from the center for a given mean anomaly
+    pub fn offset_at_mean_anomaly(&self, mean_anomaly: f32) -> Vec3 {
+        self.offset_at_eccentric_anomaly(solve_kepler(mean_anomaly, self.eccentricity))
+    }
+
+    /// Offset from the center for a given eccentric anomaly
+    /// Sampling this uniformly traces the ellipse without solving Kepler's equation
+    pub fn offset_at_eccentric_anomaly(&self, eccentric_anomaly: f32) -> Vec3 {
+        let e = self.eccentricity;
+
+        // Position in the perifocal frame (focus at origin, periapsis on +X)
+        let x = self.semi_major_axis * (eccentric_anomaly.cos() - e);
+        let z = self.semi_major_axis * (1.0 - e * e).sqrt() * eccentric_anomaly.sin();
+
+        self.orientation() * Vec3::new(x, 0.0, z)
+    }
+
+    /// Offset from the center at a given simulation time
+    /// This is a pure function of time; nothing is accumulated between frames
+    pub fn offset_at(&self, time: f64) -> Vec3 {
+        self.offset_at_mean_anomaly(self.mean_anomaly_at(time))
+    }
+}
+
+/// Solves Kepler's equation `M = E - e * sin(E)` for the eccentric anomaly `E`
+pub fn solve_kepler(mean_anomaly: f32, eccentricity: f32) -> f32 {
+    let m = mean_anomaly.rem_euclid(std::f32::consts::TAU);
+
+    // Starting at PI converges reliably for highly eccentric orbits
+    let mut e_anomaly = if eccentricity > 0.8 { std::f32::consts::PI } else { m };
+
+    for _ in 0..KEPLER_MAX_ITERATIONS {
+        let f = e_anomaly - eccentricity * e_anomaly.sin() - m;
+        let f_prime = 1.0 - eccentricity * e_anomaly.cos();
+        let delta = f / f_prime;
+        e_anomaly -= delta;
+
+        if delta.abs() < KEPLER_TOLERANCE {
+            break;
         }
     }
+
+    e_anomaly
 }
 
-/// System that updates orbital positions each frame
+/// System parameter for querying where orbiting bodies are at any time
+///
+/// Positions are resolved analytically from the simulation time, walking up
+/// parent chains, so callers can ask about the past or future without
+/// stepping the simulation.
+#[derive(SystemParam)]
+pub struct OrbitResolver<'w, 's> {
+    bodies: Query<'w, 's, (Entity, &'static OrbitalBody)>,
+    anchors: Query<'w, 's, &'static Transform, Without<OrbitalBody>>,
+}
+
+impl OrbitResolver<'_, '_> {
+    /// World position of an entity at a given simulation time
+    /// Entities without an `OrbitalBody` are treated as fixed at their current transform
+    pub fn position_at(&self, entity: Entity, time: f64) -> Option<Vec3> {
+        self.resolve(entity, time, &mut EntityHashMap::default(), 0)
+    }
+
+    /// World positions of every orbiting body at a given simulation time
+    pub fn all_positions_at(&self, time: f64) -> EntityHashMap<Vec3> {
+        let mut resolved = EntityHashMap::default();
+        for (entity, _) in self.bodies.iter() {
+            self.resolve(entity, time, &mut resolved, 0);
+        }
+        resolved
+    }
+
+    /// World position of the point an entity orbits at a given simulation time
+    pub fn center_at(&self, entity: Entity, time: f64) -> Option<Vec3> {
+        let (_, orbital) = self.bodies.get(entity).ok()?;
+        match orbital.parent {
+            Some(parent) => self.position_at(parent, time),
+            None => Some(orbital.center),
+        }
+    }
+
+    /// Resolves a position, memoizing parents so each body is solved once
+    fn resolve(
+        &self,
+        entity: Entity,
+        time: f64,
+        resolved: &mut EntityHashMap<Vec3>,
+        depth: usize,
+    ) -> Option<Vec3> {
+        if let Some(position) = resolved.get(&entity) {
+            return Some(*position);
+        }
+
+        // Bodies without an orbit (e.g. the star) are fixed anchors
+        let Ok((_, orbital)) = self.bodies.get(entity) else {
+            return self.anchors.get(entity).ok().map(|transform| transform.translation);
+        };
+
+        // Guard against cycles in misconfigured hierarchies
+        if depth > MAX_ORBIT_DEPTH {
+            return None;
+        }
+
+        let center = match orbital.parent {
+            Some(parent) => self.resolve(parent, time, resolved, depth + 1)?,
+            None => orbital.center,
+        };
+
+        let position = center + orbital.offset_at(time);
+        resolved.insert(entity, position);
+        Some(position)
+    }
+}
+
+/// System that updates orbital positions from the simulation clock
+/// Parents are resolved before their children so moons follow their planets
 pub fn update_orbits(
-    time: Res<Time>,
-    mut query: Query<(&mut OrbitalBody, &mut Transform)>,
+    clock: Res<SimulationClock>,
+    resolver: OrbitResolver,
+    mut query: Query<(Entity, &mut Transform), With<OrbitalBody>>,
 ) {
-    for (mut orbital, mut transform) in query.iter_mut() {
-        // Update the angle based on orbital speed
-        orbital.current_angle += orbital.orbital_speed * time.delta_secs();
-        
-        // Calculate new position using circular orbit
-        let x = orbital.center.x + orbital.orbital_radius * orbital.current_angle.cos();
-        let z = orbital.center.z + orbital.orbital_radius * orbital.current_angle.sin();
-        
-        // Update transform position (keeping y the same)
-        transform.translation.x = x;
-        transform.translation.z = z;
+    let positions = resolver.all_positions_at(clock.elapsed);
+
+    for (entity, mut transform) in query.iter_mut() {
+        if let Some(&position) = positions.get(&entity) {
+            transform.translation = position;
+        }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_kepler_round_trips() {
+        for eccentricity in [0.0, 0.1, 0.5, 0.9, 0.99] {
+            for step in 0..32 {
+                let mean_anomaly = step as f32 / 32.0 * std::f32::consts::TAU;
+                let e_anomaly = solve_kepler(mean_anomaly, eccentricity);
+                let back = (e_anomaly - eccentricity * e_anomaly.sin()).rem_euclid(std::f32::consts::TAU);
+                // Compare on the circle so 0 and TAU count as equal
+                let error = (back - mean_anomaly).abs();
+                let error = error.min(std::f32::consts::TAU - error);
+                assert!(error < 1e-4, "e={eccentricity} M={mean_anomaly}: got {back}");
+            }
+        }
+    }
+
+    #[test]
+    fn starts_at_periapsis() {
+        let orbit = OrbitalBody::keplerian(10.0, 0.5, 0.3, 1.0, 0.7, 0.0, 0.2);
+        let expected = orbit.orientation() * Vec3::new(10.0 * (1.0 - 0.5), 0.0, 0.0);
+        assert!(orbit.offset_at(0.0).distance(expected) < 1e-4);
+    }
+
+    #[test]
+    fn position_repeats_every_period() {
+        let orbit = OrbitalBody::keplerian(18.0, 0.2, 0.1, 0.4, 1.3, 0.5, 0.3);
+        let period = std::f64::consts::TAU / orbit.mean_motion as f64;
+        for time in [0.0, 3.7, 12.5, 100.0] {
+            let start = orbit.offset_at(time);
+            for periods in [1.0, 10.0, -3.0, 1000.0] {
+                let wrapped = orbit.offset_at(time + period * periods);
+                assert!(start.distance(wrapped) < 1e-3, "t={time} +{periods} periods");
+            }
+        }
+    }
+
+    #[test]
+    fn stepping_the_clock_lands_where_seeking_does() {
+        let orbit = OrbitalBody::keplerian(5.0, 0.6, 0.2, 0.3, 0.4, 2.0, 1.1);
+        let direct = orbit.offset_at(42.0);
+
+        let mut app = App::new();
+        app.init_resource::<SimulationClock>()
+            .add_systems(Update, update_orbits);
+        let body = app.world_mut().spawn((orbit, Transform::default())).id();
+        let step = |app: &mut App, frames: usize, delta: f64, reversed: bool| {
+            for _ in 0..frames {
+                let mut clock = app.world_mut().resource_mut::<SimulationClock>();
+                clock.reversed = reversed;
+                clock.tick(delta);
+                app.update();
+            }
+        };
+
+        // Forward past the target in uneven frames, then back to it in reverse
+        step(&mut app, 600, 0.1, false);
+        step(&mut app, 300, 0.06, true);
+        let clock = app.world().resource::<SimulationClock>();
+        assert!((clock.elapsed - 42.0).abs() < 1e-9, "clock at {}", clock.elapsed);
+
+        let stepped = app.world().get::<Transform>(body).unwrap().translation;
+        assert!(stepped.distance(direct) < 1e-4, "stepped to {stepped}, direct {direct}");
+    }
+}
@@ -0,0 +1,100 @@
+use bevy::{ecs::entity::EntityHashMap, prelude::*};
+use crate::gravity::NBodyState;
+use crate::orbital::OrbitResolver;
+use crate::simulation::SimulationClock;
+
+/// Component for bodies that spin about their own axis
+#[derive(Component)]
+pub struct AxialRotation {
+    /// Time for one full turn, in simulated seconds (negative = retrograde, 0 = no spin)
+    pub sidereal_period: f32,
+    /// Tilt of the spin axis away from +Y, in radians
+    pub axial_tilt: f32,
+    /// Spin angle at epoch (time zero), in radians
+    pub rotation_at_epoch: f32,
+    /// When true, the body keeps the same face toward whatever it orbits
+    pub tidally_locked: bool,
+}
+
+impl AxialRotation {
+    pub fn new(sidereal_period: f32, axial_tilt: f32) -> Self {
+        Self {
+            sidereal_period,
+            axial_tilt,
+            rotation_at_epoch: 0.0,
+            tidally_locked: false,
+        }
+    }
+
+    /// Keeps the body's +X face pointed at its orbital parent (or center)
+    pub fn with_tidal_lock(mut self) -> Self {
+        self.tidally_locked = true;
+        self
+    }
+
+    /// Rotation that tilts the spin axis away from +Y
+    pub fn tilt(&self) -> Quat {
+        Quat::from_rotation_z(self.axial_tilt)
+    }
+
+    /// Spin angle about the tilted axis at a given simulation time
+    pub fn spin_angle_at(&self, time: f64) -> f32 {
+        if self.sidereal_period == 0.0 {
+            return self.rotation_at_epoch;
+        }
+        // Computed in f64 so large warped times don't lose precision
+        let turns = time / self.sidereal_period as f64;
+        (self.rotation_at_epoch as f64 + turns * std::f64::consts::TAU)
+            .rem_euclid(std::f64::consts::TAU) as f32
+    }
+}
+
+/// Spinning bodies, whose transforms `update_rotations` writes
+type SpinningBodies<'w, 's> = Query<'w, 's, (Entity, &'static AxialRotation, &'static mut Transform)>;
+
+/// System that orients spinning bodies from the simulation clock
+/// Tidally locked N-bodies keep facing what they orbited before leaving their rails
+pub fn update_rotations(
+    clock: Res<SimulationClock>,
+    nbodies: Query<&NBodyState>,
+    mut params: ParamSet<(OrbitResolver, SpinningBodies)>,
+) {
+    let time = clock.elapsed;
+
+    // Work out where each tidally locked body's parent is before touching transforms
+    let locked: Vec<Entity> = params
+        .p1()
+        .iter()
+        .filter(|(_, rotation, _)| rotation.tidally_locked)
+        .map(|(entity, _, _)| entity)
+        .collect();
+    let mut to_parent = EntityHashMap::default();
+    let resolver = params.p0();
+    for entity in locked {
+        let center = resolver.center_at(entity, time).or_else(|| {
+            let state = nbodies.get(entity).ok()?;
+            match state.parent {
+                Some(parent) => resolver.position_at(parent, time),
+                None => Some(state.center),
+            }
+        });
+        if let (Some(position), Some(center)) = (resolver.position_at(entity, time), center) {
+            to_parent.insert(entity, center - position);
+        }
+    }
+
+    for (entity, rotation, mut transform) in params.p1().iter_mut() {
+        let tilt = rotation.tilt();
+        let mut angle = rotation.spin_angle_at(time);
+
+        if let Some(direction) = to_parent.get(&entity) {
+            // Yaw about the tilted axis so local +X faces the parent
+            let local = tilt.inverse() * *direction;
+            if local.x != 0.0 || local.z != 0.0 {
+                angle = (-local.z).atan2(local.x);
+            }
+        }
+
+        transform.rotation = tilt * Quat::from_rotation_y(angle);
+    }
+}
@@ -1,15 +1,36 @@
 use bevy::{
     diagnostic::{FrameTimeDiagnosticsPlugin, EntityCountDiagnosticsPlugin},
     prelude::*,
+    camera::CameraUpdateSystems,
+    transform::TransformSystems,
+    ui::UiSystems,
+};
+use crate::atmosphere::{GasGiantMaterial, animate_atmospheres};
+use crate::camera::{
+    setup_camera, toggle_cursor_lock, camera_look, camera_movement, orbit_camera, select_orbit_target,
+    follow_body, follow_home_planet, toggle_follow_body,
+};
+use crate::constellations::{
+    ConstellationOverlay, ConstellationSet, ConstellationSetLoader, draw_constellation_lines,
+    fade_constellations, load_constellations, position_constellation_labels,
+    spawn_constellation_labels, toggle_constellations,
 };
-use crate::camera::{setup_camera, toggle_cursor_lock, camera_look, camera_movement};
 use crate::debug_ui::{setup_debug_ui, update_debug_stats};
 use crate::entities::spawn_entities;
+use crate::gravity::{NBodySettings, integrate_nbody, limit_nbody_warp, switch_to_nbody, toggle_nbody_mode};
 use crate::lighting::setup_lighting;
+use crate::orbit_paths::{OrbitPathSettings, draw_orbit_paths, toggle_orbit_paths};
 use crate::orbital::update_orbits;
-use crate::skybox::setup_skybox;
-// Starfield removed in favor of skybox
-// use crate::starfield::spawn_starfield;
+use crate::rotation::update_rotations;
+use crate::simulation::{SimulationClock, advance_simulation_clock, simulation_clock_controls};
+use crate::skybox::{
+    SkyboxConfig, apply_skybox_config, prepare_skybox_images, render_catalog_skyboxes, setup_skybox,
+};
+use crate::star_catalog::{StarCatalog, StarCatalogLoader};
+use crate::star_system::{StarSystemDefinition, StarSystemLoader, load_star_system};
+use crate::starfield::{StarfieldConfig, StarfieldMaterial, follow_camera_starfield, spawn_starfield};
+use crate::system_generator::regenerate_star_system;
+use crate::texture_tasks::{TextureGenerationProgress, poll_texture_tasks};
 
 /// Plugin that orchestrates all scene setup systems
 pub struct SceneSetupPlugin;
@@ -22,6 +43,14 @@ impl Plugin for SceneSetupPlugin {
                 FrameTimeDiagnosticsPlugin::default(),
                 EntityCountDiagnosticsPlugin::default(),
             ))
+            // Gas giant material with animated cloud bands
+            .add_plugins(MaterialPlugin::<GasGiantMaterial>::default())
+            // Twinkling point-sprite stars drawn over the skybox; additive, so no shadows or prepass
+            .add_plugins(MaterialPlugin::<StarfieldMaterial> {
+                prepass_enabled: false,
+                shadows_enabled: false,
+                ..default()
+            })
             // Set the space background color (black)
             .insert_resource(ClearColor(Color::srgb(0.0, 0.0, 0.0)))
             // Insert ambient light (space ambient light - increased for visibility)
@@ -30,12 +59,31 @@ impl Plugin for SceneSetupPlugin {
                 brightness: 200.0,
                 affects_lightmapped_meshes: false,
             })
+            // Star systems are data-driven and hot-reload from assets/systems
+            .init_asset::<StarSystemDefinition>()
+            .init_asset_loader::<StarSystemLoader>()
+            // Star catalogs for realistic skyboxes
+            .init_asset::<StarCatalog>()
+            .init_asset_loader::<StarCatalogLoader>()
+            // Constellation figures for the sky overlay
+            .init_asset::<ConstellationSet>()
+            .init_asset_loader::<ConstellationSetLoader>()
+            // Simulation time driving all orbital motion
+            .init_resource::<SimulationClock>()
+            .init_resource::<NBodySettings>()
+            .init_resource::<OrbitPathSettings>()
+            .init_resource::<TextureGenerationProgress>()
+            .init_resource::<SkyboxConfig>()
+            .init_resource::<ConstellationOverlay>()
+            .init_resource::<StarfieldConfig>()
             // Add setup systems (skybox must run after camera setup)
             .add_systems(Startup, (
                 setup_camera,
-                spawn_entities,
+                load_star_system,
                 setup_lighting,
                 setup_debug_ui,
+                load_constellations,
+                spawn_starfield,
             ))
             .add_systems(Startup, setup_skybox.after(setup_camera))
             // Add runtime systems for camera control and orbital mechanics
@@ -45,7 +93,63 @@ impl Plugin for SceneSetupPlugin {
                 camera_movement,
                 update_orbits,
                 update_debug_stats,
-            ));
+            ))
+            // Procedural textures are generated off the main thread
+            .add_systems(Update, poll_texture_tasks)
+            // Skybox regenerates when its config changes; loaded images become cubemaps
+            .add_systems(Update, (
+                apply_skybox_config,
+                prepare_skybox_images,
+                render_catalog_skyboxes,
+            ).chain())
+            // Constellation overlay fades in and out with C
+            .add_systems(Update, (
+                toggle_constellations,
+                fade_constellations,
+                spawn_constellation_labels,
+            ).chain())
+            // Overlay follows the camera's final transform for the frame, before UI layout
+            .add_systems(PostUpdate, (
+                draw_constellation_lines,
+                position_constellation_labels,
+            ).after(CameraUpdateSystems).before(UiSystems::Prepare))
+            // Distant stars stay centered on the camera like the skybox
+            .add_systems(PostUpdate, follow_camera_starfield.before(TransformSystems::Propagate))
+            // Bodies are spawned once their definition finishes loading
+            .add_systems(Update, (
+                regenerate_star_system,
+                spawn_entities,
+            ).chain().before(update_orbits))
+            // Clock must advance before orbits read it; N-body mode caps its warp
+            .add_systems(Update, (
+                simulation_clock_controls,
+                limit_nbody_warp,
+                advance_simulation_clock,
+            ).chain().before(update_orbits))
+            // N-body integration reads rail positions as fixed gravity sources
+            .add_systems(Update, (
+                toggle_nbody_mode,
+                switch_to_nbody,
+                integrate_nbody,
+            ).chain().after(update_orbits))
+            // Spin and tidal locking use the same clock as the orbits, and
+            // see N-bodies where this frame's integration left them
+            .add_systems(Update, update_rotations.after(integrate_nbody))
+            // Following and orbiting cameras catch up after every system that moves bodies
+            .add_systems(Update, (
+                select_orbit_target,
+                toggle_follow_body,
+                follow_home_planet,
+                follow_body,
+                orbit_camera,
+            ).chain().after(integrate_nbody).after(update_rotations))
+            // Cloud bands drift with simulation time too
+            .add_systems(Update, animate_atmospheres.after(advance_simulation_clock))
+            // Orbit lines are drawn around the freshly updated parent positions
+            .add_systems(Update, (
+                toggle_orbit_paths,
+                draw_orbit_paths,
+            ).chain().after(update_orbits));
     }
 }
 
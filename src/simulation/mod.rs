@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+
+/// Discrete time warp multipliers, cycled with the comma/period keys
+pub const WARP_STEPS: [f64; 6] = [1.0, 10.0, 100.0, 1_000.0, 10_000.0, 100_000.0];
+
+/// Resource tracking simulated time, independent of real frame time
+///
+/// Orbits are computed from `elapsed` (seconds since the epoch), so pausing,
+/// warping or reversing the clock moves every body consistently.
+#[derive(Resource, Default)]
+pub struct SimulationClock {
+    /// Simulated seconds since the epoch (may be negative when reversed)
+    pub elapsed: f64,
+    /// When true, simulated time does not advance
+    pub paused: bool,
+    /// When true, simulated time runs backwards
+    pub reversed: bool,
+    /// Index into `WARP_STEPS`
+    pub warp_index: usize,
+}
+
+impl SimulationClock {
+    /// Current warp multiplier (always positive)
+    pub fn warp(&self) -> f64 {
+        WARP_STEPS[self.warp_index.min(WARP_STEPS.len() - 1)]
+    }
+
+    /// Simulated seconds per real second, including direction and pause
+    pub fn rate(&self) -> f64 {
+        if self.paused {
+            0.0
+        } else if self.reversed {
+            -self.warp()
+        } else {
+            self.warp()
+        }
+    }
+
+    /// Simulated seconds since the epoch as `f32`, for per-frame math
+    pub fn elapsed_secs(&self) -> f32 {
+        self.elapsed as f32
+    }
+
+    /// Advances the clock by a real-time delta
+    pub fn tick(&mut self, real_delta_secs: f64) {
+        self.elapsed += real_delta_secs * self.rate();
+    }
+
+    /// Jumps directly to a simulation time
+    /// Orbits are analytic, so every body lands exactly where it would have been
+    pub fn seek(&mut self, elapsed: f64) {
+        self.elapsed = elapsed;
+    }
+
+    /// Steps to the next faster warp setting
+    pub fn increase_warp(&mut self) {
+        self.warp_index = (self.warp_index + 1).min(WARP_STEPS.len() - 1);
+    }
+
+    /// Steps to the next slower warp setting
+    pub fn decrease_warp(&mut self) {
+        self.warp_index = self.warp_index.saturating_sub(1);
+    }
+}
+
+/// System that advances the simulation clock by the scaled frame time
+pub fn advance_simulation_clock(time: Res<Time>, mut clock: ResMut<SimulationClock>) {
+    clock.tick(time.delta_secs_f64());
+}
+
+/// System that handles keyboard control of the simulation clock
+/// P: pause, R: reverse, comma/period: slower/faster warp, Home: back to epoch
+pub fn simulation_clock_controls(
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut clock: ResMut<SimulationClock>,
+) {
+    if key_input.just_pressed(KeyCode::KeyP) {
+        clock.paused = !clock.paused;
+    }
+    if key_input.just_pressed(KeyCode::KeyR) {
+        clock.reversed = !clock.reversed;
+    }
+    if key_input.just_pressed(KeyCode::Period) {
+        clock.increase_warp();
+    }
+    if key_input.just_pressed(KeyCode::Comma) {
+        clock.decrease_warp();
+    }
+    if key_input.just_pressed(KeyCode::Home) {
+        clock.seek(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advances_at_the_warp_rate() {
+        let mut clock = SimulationClock::default();
+        clock.tick(0.5);
+        assert_eq!(clock.elapsed, 0.5);
+
+        clock.increase_warp();
+        clock.increase_warp();
+        clock.tick(0.5);
+        assert_eq!(clock.elapsed, 0.5 + 0.5 * WARP_STEPS[2]);
+    }
+
+    #[test]
+    fn reversing_runs_time_backwards_past_the_epoch() {
+        let mut clock = SimulationClock::default();
+        clock.tick(2.0);
+        clock.reversed = true;
+        clock.tick(5.0);
+        assert_eq!(clock.rate(), -1.0);
+        assert_eq!(clock.elapsed, -3.0);
+    }
+
+    #[test]
+    fn pausing_stops_time() {
+        let mut clock = SimulationClock::default();
+        clock.increase_warp();
+        clock.tick(1.0);
+        clock.paused = true;
+        clock.tick(1.0);
+        assert_eq!(clock.rate(), 0.0);
+        assert_eq!(clock.elapsed, WARP_STEPS[1]);
+    }
+
+    #[test]
+    fn warp_clamps_at_both_ends() {
+        let mut clock = SimulationClock::default();
+        clock.decrease_warp();
+        assert_eq!(clock.warp(), WARP_STEPS[0]);
+
+        for _ in 0..WARP_STEPS.len() + 2 {
+            clock.increase_warp();
+        }
+        assert_eq!(clock.warp_index, WARP_STEPS.len() - 1);
+        assert_eq!(clock.warp(), WARP_STEPS[WARP_STEPS.len() - 1]);
+    }
+
+    #[test]
+    fn home_seeks_back_to_the_epoch() {
+        let mut clock = SimulationClock::default();
+        clock.increase_warp();
+        clock.tick(3.0);
+
+        let mut app = App::new();
+        app.insert_resource(clock)
+            .init_resource::<ButtonInput<KeyCode>>()
+            .add_systems(Update, simulation_clock_controls);
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::Home);
+        app.update();
+
+        let clock = app.world().resource::<SimulationClock>();
+        assert_eq!(clock.elapsed, 0.0);
+        // Seeking keeps the warp setting
+        assert_eq!(clock.warp_index, 1);
+    }
+}
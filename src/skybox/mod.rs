@@ -1,33 +1,212 @@
 use bevy::{
     prelude::*,
     core_pipeline::Skybox,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureViewDescriptor, TextureViewDimension},
 };
+use serde::Serialize;
+use crate::noise::equirect_uv;
+use crate::star_catalog::{DEFAULT_STAR_CATALOG_PATH, StarCatalog};
+use crate::stellar::blackbody_color;
+use crate::texture_cache::{TextureCacheKey, load_or_generate};
+use crate::texture_tasks::{regenerate_texture, spawn_texture_task};
+
+mod nebula;
+mod splat;
+
+pub use nebula::{GalacticBand, NebulaLayer};
+use nebula::SkyGlow;
+use splat::{CubemapCanvas, srgb_to_linear};
+
+/// Environment variable selecting a skybox asset instead of the procedural starfield
+/// `.hdr` and `.exr` files are treated as equirectangular panoramas, `.csv` files as
+/// star catalogs and anything else as a cubemap
+pub const SKYBOX_ENV_VAR: &str = "SKYBOX";
+
+/// Cubemap face order: +X, -X, +Y, -Y, +Z, -Z
+/// Each face is a view direction with up and right vectors, following the
+/// standard (wgpu/D3D) layout where the first texel row is the top of the face
+const CUBE_FACES: [(&str, Vec3, Vec3, Vec3); 6] = [
+    ("PosX", Vec3::X, Vec3::Y, Vec3::NEG_Z),
+    ("NegX", Vec3::NEG_X, Vec3::Y, Vec3::Z),
+    ("PosY", Vec3::Y, Vec3::NEG_Z, Vec3::X),
+    ("NegY", Vec3::NEG_Y, Vec3::Z, Vec3::X),
+    ("PosZ", Vec3::Z, Vec3::Y, Vec3::X),
+    ("NegZ", Vec3::NEG_Z, Vec3::Y, Vec3::NEG_X),
+];
+
+/// Converts between cubemap and world directions (the conversion is its own inverse)
+/// Bevy's skybox samples cubemaps with Z flipped, since cube space is left-handed
+pub(crate) fn cube_to_world(direction: Vec3) -> Vec3 {
+    direction * Vec3::new(1.0, 1.0, -1.0)
+}
+
+/// Direction in the world where a direction on the sky shows up
+/// Overlays use this to stay lined up with the skybox as it's rotated
+pub fn sky_to_world(skybox: &Skybox, direction: Vec3) -> Vec3 {
+    skybox.rotation * direction
+}
+
+/// Version of the starfield generator, part of the texture cache key
+/// Bump whenever the output changes for the same `SkyboxConfig`
+pub const SKYBOX_GENERATOR_VERSION: u32 = 6;
+
+/// Nebulae and the galactic band are smooth, so they're evaluated on a grid
+/// this many times coarser than the face and interpolated
+const GLOW_DOWNSAMPLE: u32 = 8;
+
+/// Face resolution at which star densities count stars per texel
+const STAR_REFERENCE_RESOLUTION: u32 = 2048;
+
+/// Width of a star's point spread in texels at the reference resolution
+const STAR_SIGMA: f32 = 0.8;
+
+/// Light of a catalog star at the magnitude limit, in fully lit texels at 2048 per face
+const CATALOG_LIMIT_ENERGY: f32 = 0.4;
+
+/// Where the skybox image comes from
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum SkyboxSource {
+    /// Procedural starfield generated from the rest of `SkyboxConfig`
+    #[default]
+    Procedural,
+    /// Cubemap image asset: vertically stacked faces (PNG etc.) or a KTX2/DDS cubemap
+    Cubemap(String),
+    /// Equirectangular panorama asset, converted to a cubemap once loaded
+    Equirectangular(String),
+    /// Star catalog CSV (see `StarCatalogLoader`), rendered into a cubemap once loaded
+    Catalog(String),
+}
+
+impl SkyboxSource {
+    /// Picks the source type from an asset path's extension
+    pub fn from_path(path: &str) -> Self {
+        let extension = path.rsplit('.').next().unwrap_or_default().to_ascii_lowercase();
+        match extension.as_str() {
+            "hdr" | "exr" => SkyboxSource::Equirectangular(path.to_string()),
+            "csv" => SkyboxSource::Catalog(path.to_string()),
+            _ => SkyboxSource::Cubemap(path.to_string()),
+        }
+    }
+}
+
+/// Settings for the skybox and its procedural starfield
+/// Insert before `SceneSetupPlugin` to override the defaults, e.g. `SkyboxConfig::preview()`
+/// Changing it at runtime regenerates the cubemap in the background
+#[derive(Resource, Debug, Clone, PartialEq, Serialize)]
+pub struct SkyboxConfig {
+    /// Resolution per cube face (512, 1024, 2048, 4096)
+    /// Higher = crisper stars but slower generation
+    pub resolution: u32,
+    /// Seed for star placement and colors
+    pub seed: u64,
+    /// Bright stars per texel of a 2048 face; the star layout itself doesn't depend on resolution
+    pub bright_density: f32,
+    /// Medium stars per texel of a 2048 face
+    pub medium_density: f32,
+    /// Dim stars per texel of a 2048 face
+    pub dim_density: f32,
+    /// How strongly stars are tinted by a random blackbody color (0 = uniform bluish white)
+    pub color_variation: f32,
+    /// Color of empty space (sRGB)
+    pub background: (f32, f32, f32),
+    /// Nebula cloud layers, summed on top of the background
+    pub nebulae: Vec<NebulaLayer>,
+    /// Milky Way-like band with dust lanes and extra stars
+    pub galactic_band: Option<GalacticBand>,
+    /// Faintest apparent magnitude drawn from a star catalog (naked eye is about 6.5)
+    pub catalog_magnitude_limit: f32,
+    /// Skybox brightness; applied directly, without regenerating the cubemap
+    #[serde(skip)]
+    pub brightness: f32,
+    /// Procedural starfield or an image asset
+    #[serde(skip)]
+    pub source: SkyboxSource,
+}
+
+impl Default for SkyboxConfig {
+    fn default() -> Self {
+        Self {
+            resolution: 2048,
+            seed: 42,
+            bright_density: 0.0005,
+            medium_density: 0.0008,
+            dim_density: 0.001,
+            color_variation: 0.0,
+            background: (1.0 / 255.0, 1.0 / 255.0, 3.0 / 255.0),
+            nebulae: vec![NebulaLayer::haze(), NebulaLayer::emission()],
+            galactic_band: Some(GalacticBand::default()),
+            catalog_magnitude_limit: 6.5,
+            brightness: 500.0,  // Toned down for subtlety
+            source: std::env::var(SKYBOX_ENV_VAR)
+                .map(|path| SkyboxSource::from_path(&path))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl SkyboxConfig {
+    /// Low resolution skybox for fast iteration
+    pub fn preview() -> Self {
+        Self {
+            resolution: 512,
+            ..default()
+        }
+    }
+
+    /// High resolution skybox for screenshots and videos
+    pub fn capture() -> Self {
+        Self {
+            resolution: 4096,
+            ..default()
+        }
+    }
+
+    /// Real night sky from the bundled star catalog, for recognizable constellations
+    pub fn real_sky() -> Self {
+        Self {
+            source: SkyboxSource::Catalog(DEFAULT_STAR_CATALOG_PATH.to_string()),
+            ..default()
+        }
+    }
+
+    /// Whether two configs produce the same cubemap
+    fn same_image(&self, other: &SkyboxConfig) -> bool {
+        *self == SkyboxConfig { brightness: self.brightness, ..other.clone() }
+    }
+
+    /// Cache key of the cubemap this config produces, if it has one
+    pub fn cache_key(&self) -> Option<TextureCacheKey> {
+        TextureCacheKey::new("skybox", SKYBOX_GENERATOR_VERSION, self)
+    }
+}
 
 /// Component to mark cameras that should have a skybox
 #[derive(Component)]
 pub struct SkyboxCamera;
 
+/// Panorama the camera's skybox is converted from once it loads
+#[derive(Component)]
+pub struct EquirectangularSkybox(pub Handle<Image>);
+
+/// Star catalog the camera's skybox is rendered from once it loads
+#[derive(Component)]
+pub struct CatalogSkybox(pub Handle<StarCatalog>);
+
 /// System that adds a skybox to the camera
-/// Note: For now uses a simple dark color. To use a texture:
-/// 1. Download a space skybox (e.g., from polyhaven.com)
-/// 2. Place in assets/textures/skybox.png
-/// 3. Uncomment the asset loading code below
+/// The image comes from `SkyboxConfig::source`, see `SKYBOX_ENV_VAR`
 pub fn setup_skybox(
     mut commands: Commands,
     camera_query: Query<Entity, (With<Camera3d>, Without<Skybox>)>,
-    _asset_server: Res<AssetServer>,
+    asset_server: Res<AssetServer>,
+    config: Res<SkyboxConfig>,
     mut images: ResMut<Assets<Image>>,
 ) {
     for camera_entity in camera_query.iter() {
-        // Option 1: Use a texture file (uncomment if you have a skybox texture)
-        // let skybox_handle = asset_server.load("textures/skybox.png");
-        
-        // Option 2: Create a simple procedural dark space texture
-        let skybox_handle = create_simple_space_skybox(&mut images);
+        let skybox_handle = skybox_image(&mut commands, camera_entity, &asset_server, &mut images, &config);
         
         commands.entity(camera_entity).insert(Skybox {
             image: skybox_handle,
-            brightness: 500.0,  // Toned down for subtlety
+            brightness: config.brightness,
             ..default()
         });
         
@@ -35,132 +214,390 @@ pub fn setup_skybox(
     }
 }
 
-/// Creates a simple dark space procedural texture with seamless starfield
-fn create_simple_space_skybox(images: &mut ResMut<Assets<Image>>) -> Handle<Image> {
-    use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureViewDimension};
-    use rand::SeedableRng;
-    use rand::rngs::StdRng;
-    
-    // ========== CONFIGURATION ==========
-    // Adjust these values to control star appearance:
-    
-    // Resolution per face (512, 1024, 2048, 4096)
-    // Higher = crisper stars but slower startup
-    let size = 2048u32;
-    
-    let bytes_per_pixel = 4usize;
-    
-    // Create stacked 2D image: 6 faces vertically (posx, negx, posy, negy, posz, negz)
-    // This matches Bevy's expected cubemap format
-    let total_height = size * 6;
-    let mut image_data = vec![0u8; (size * total_height) as usize * bytes_per_pixel];
-    
-    let _rng = StdRng::seed_from_u64(42);
-    
-    info!("Generating high-res starfield skybox: {}x{} per face...", size, size);
-    
-    // Cubemap face order: +X, -X, +Y, -Y, +Z, -Z
-    // Each face is a view direction with up and right vectors
-    let faces = [
-        ("PosX", Vec3::X, Vec3::NEG_Y, Vec3::NEG_Z),
-        ("NegX", Vec3::NEG_X, Vec3::NEG_Y, Vec3::Z),
-        ("PosY", Vec3::Y, Vec3::Z, Vec3::X),
-        ("NegY", Vec3::NEG_Y, Vec3::NEG_Z, Vec3::X),
-        ("PosZ", Vec3::Z, Vec3::NEG_Y, Vec3::X),
-        ("NegZ", Vec3::NEG_Z, Vec3::NEG_Y, Vec3::NEG_X),
-    ];
-    
-    for (face_idx, (_name, forward, up, right)) in faces.iter().enumerate() {
-        let face_offset = face_idx * size as usize * size as usize * bytes_per_pixel;
-        
-        for y in 0..size {
-            for x in 0..size {
-                // Map to [-1, 1] range
-                let u = (x as f32 / (size - 1) as f32) * 2.0 - 1.0;
-                let v = 1.0 - (y as f32 / (size - 1) as f32) * 2.0;
-                
-                // Get 3D direction for this pixel
-                let direction = (*forward + *right * u + *up * v).normalize();
-                
-                // Use multiple scales for star variety and seamlessness
-                let scale1 = 15.0;
-                let scale2 = 30.0;
-                let scale3 = 60.0;
-                
-                let p1 = direction * scale1;
-                let p2 = direction * scale2;
-                let p3 = direction * scale3;
-                
-                let hash1 = ((p1.x * 12.9898 + p1.y * 78.233 + p1.z * 45.164).sin() * 43758.5453).fract();
-                let hash2 = ((p2.x * 17.1234 + p2.y * 91.567 + p2.z * 23.891).sin() * 27182.8182).fract();
-                let hash3 = ((p3.x * 31.4159 + p3.y * 62.831 + p3.z * 14.142).sin() * 31415.9265).fract();
-                
-                let pixel_idx = face_offset + (y * size + x) as usize * bytes_per_pixel;
-                
-                // Star density thresholds (LOWER = MORE STARS)
-                // Fine-grained control: 0.990-0.999
-                // Examples: 0.998 (sparse), 0.9985 (medium), 0.999 (very sparse), 0.9995 (ultra sparse)
-                const BRIGHT_THRESHOLD: f32 = 0.9995;  // Bright stars
-                const MEDIUM_THRESHOLD: f32 = 0.9992;  // Medium stars  
-                const DIM_THRESHOLD: f32 = 0.9990;     // Dim stars
-                
-                let mut is_star = false;
-                let mut brightness = 0u8;
-                
-                // Bright stars (sparse)
-                if hash1 > BRIGHT_THRESHOLD {
-                    is_star = true;
-                    brightness = ((hash1 - BRIGHT_THRESHOLD) / (1.0 - BRIGHT_THRESHOLD) * 150.0 + 100.0) as u8;
-                }
-                // Medium stars (more common)
-                else if hash2 > MEDIUM_THRESHOLD {
-                    is_star = true;
-                    brightness = ((hash2 - MEDIUM_THRESHOLD) / (1.0 - MEDIUM_THRESHOLD) * 100.0 + 60.0) as u8;
-                }
-                // Dim stars (even more common)
-                else if hash3 > DIM_THRESHOLD {
-                    is_star = true;
-                    brightness = ((hash3 - DIM_THRESHOLD) / (1.0 - DIM_THRESHOLD) * 80.0 + 40.0) as u8;
-                }
-                
-                if is_star {
-                    image_data[pixel_idx] = brightness;
-                    image_data[pixel_idx + 1] = brightness;
-                    image_data[pixel_idx + 2] = brightness.saturating_add(15);
-                    image_data[pixel_idx + 3] = 255;
-                } else {
-                    // Very dark space background
-                    image_data[pixel_idx] = 1;
-                    image_data[pixel_idx + 1] = 1;
-                    image_data[pixel_idx + 2] = 3;
-                    image_data[pixel_idx + 3] = 255;
+/// Starts loading or generating the skybox image for a camera
+fn skybox_image(
+    commands: &mut Commands,
+    camera_entity: Entity,
+    asset_server: &AssetServer,
+    images: &mut Assets<Image>,
+    config: &SkyboxConfig,
+) -> Handle<Image> {
+    commands.entity(camera_entity).remove::<(EquirectangularSkybox, CatalogSkybox)>();
+    match &config.source {
+        // Generated in the background; plain dark space shows until it's ready
+        SkyboxSource::Procedural => {
+            let generator_config = config.clone();
+            spawn_texture_task(
+                commands,
+                images,
+                skybox_placeholder(config),
+                move || load_or_generate(generator_config.cache_key(), || {
+                    create_simple_space_skybox(&generator_config)
+                }),
+            )
+        }
+        // Made cube-compatible by prepare_skybox_images once loaded
+        SkyboxSource::Cubemap(path) => asset_server.load(path.clone()),
+        // Dark space until the panorama is loaded and converted
+        SkyboxSource::Equirectangular(path) => {
+            let panorama = asset_server.load(path.clone());
+            commands.entity(camera_entity).insert(EquirectangularSkybox(panorama));
+            images.add(skybox_placeholder(config))
+        }
+        // Dark space until the catalog is loaded and rendered
+        SkyboxSource::Catalog(path) => {
+            let catalog = asset_server.load(path.clone());
+            commands.entity(camera_entity).insert(CatalogSkybox(catalog));
+            images.add(skybox_placeholder(config))
+        }
+    }
+}
+
+/// System that applies `SkyboxConfig` changes to existing skyboxes
+/// The old cubemap stays visible while a new procedural one is generated
+pub fn apply_skybox_config(
+    mut commands: Commands,
+    config: Res<SkyboxConfig>,
+    mut applied: Local<Option<SkyboxConfig>>,
+    mut skyboxes: Query<(Entity, &mut Skybox)>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if !config.is_changed() {
+        return;
+    }
+
+    // The first run only records the config setup_skybox started with
+    let Some(previous) = applied.replace(config.clone()) else {
+        return;
+    };
+    let changed = !previous.same_image(&config);
+    let regenerate_in_place = changed
+        && previous.source == SkyboxSource::Procedural
+        && config.source == SkyboxSource::Procedural;
+
+    for (camera_entity, mut skybox) in skyboxes.iter_mut() {
+        skybox.brightness = config.brightness;
+        if regenerate_in_place {
+            let generator_config = config.clone();
+            regenerate_texture(&mut commands, skybox.image.clone(), move || {
+                load_or_generate(generator_config.cache_key(), || {
+                    create_simple_space_skybox(&generator_config)
+                })
+            });
+        } else if changed {
+            skybox.image = skybox_image(&mut commands, camera_entity, &asset_server, &mut images, &config);
+        }
+    }
+}
+
+/// System that turns loaded skybox assets into cubemaps
+/// Stacked images are reinterpreted as six layers and panoramas are converted
+/// in the background; reacting to modifications keeps hot reloading working
+pub fn prepare_skybox_images(
+    mut commands: Commands,
+    mut events: MessageReader<AssetEvent<Image>>,
+    mut images: ResMut<Assets<Image>>,
+    skyboxes: Query<(&Skybox, Option<Ref<EquirectangularSkybox>>)>,
+) {
+    let updated: Vec<AssetId<Image>> = events
+        .read()
+        .filter_map(|event| match *event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(id),
+            _ => None,
+        })
+        .collect();
+
+    for (skybox, panorama) in skyboxes.iter() {
+        match panorama {
+            // A newly selected panorama may have been loaded before
+            Some(panorama) if panorama.is_added() || updated.contains(&panorama.0.id()) => {
+                let Some(source) = images.get(&panorama.0) else {
+                    continue;
+                };
+                let source = source.clone();
+                let face_size = (source.width() / 4).max(1);
+                info!("Converting equirectangular skybox to {}x{} cubemap faces", face_size, face_size);
+                regenerate_texture(&mut commands, skybox.image.clone(), move || {
+                    equirect_to_cubemap(&source, face_size)
+                });
+            }
+            None if updated.contains(&skybox.image.id()) => {
+                // Only touch images that still need it, since get_mut sends another Modified event
+                let id = skybox.image.id();
+                if images.get(id).is_some_and(needs_cube_view)
+                    && let Some(image) = images.get_mut(id)
+                {
+                    make_cubemap(image);
                 }
             }
+            _ => {}
         }
     }
-    
-    info!("Starfield generation complete!");
-    
-    // Create image as stacked 2D (width x height*6)
-    let mut image = Image::new(
+}
+
+/// System that renders loaded star catalogs into their skyboxes
+pub fn render_catalog_skyboxes(
+    mut commands: Commands,
+    mut events: MessageReader<AssetEvent<StarCatalog>>,
+    catalogs: Res<Assets<StarCatalog>>,
+    config: Res<SkyboxConfig>,
+    skyboxes: Query<(&Skybox, Ref<CatalogSkybox>)>,
+) {
+    let updated: Vec<AssetId<StarCatalog>> = events
+        .read()
+        .filter_map(|event| match *event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(id),
+            _ => None,
+        })
+        .collect();
+
+    for (skybox, catalog) in skyboxes.iter() {
+        if !catalog.is_added() && !updated.contains(&catalog.0.id()) {
+            continue;
+        }
+        let Some(stars) = catalogs.get(&catalog.0) else {
+            continue;
+        };
+        let stars = stars.clone();
+        let render_config = config.clone();
+        regenerate_texture(&mut commands, skybox.image.clone(), move || {
+            render_star_catalog(&stars, &render_config)
+        });
+    }
+}
+
+/// Whether a loaded image still has to be set up as a cubemap
+fn needs_cube_view(image: &Image) -> bool {
+    image
+        .texture_view_descriptor
+        .as_ref()
+        .and_then(|view| view.dimension)
+        != Some(TextureViewDimension::Cube)
+}
+
+/// Sets up a loaded image as a cubemap
+/// Stacked 2D images (height six times the width) are split into six layers first
+fn make_cubemap(image: &mut Image) {
+    if image.texture_descriptor.array_layer_count() == 1 && image.height() == image.width() * 6 {
+        image.reinterpret_stacked_2d_as_array(6);
+    }
+    if image.texture_descriptor.array_layer_count() != 6 {
+        warn!(
+            "Skybox image is {}x{} with {} layers, expected six stacked faces or a cubemap",
+            image.width(),
+            image.height(),
+            image.texture_descriptor.array_layer_count(),
+        );
+        return;
+    }
+    image.texture_view_descriptor = Some(TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::Cube),
+        ..Default::default()
+    });
+}
+
+/// Resamples an equirectangular panorama into a cubemap
+/// Output is 16-bit float so HDR panoramas keep their range
+pub fn equirect_to_cubemap(source: &Image, face_size: u32) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: face_size,
+            height: face_size * 6,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0; 8],
+        TextureFormat::Rgba16Float,
+        Default::default(),
+    );
+
+    for (face_idx, (_name, forward, up, right)) in CUBE_FACES.iter().enumerate() {
+        for y in 0..face_size {
+            for x in 0..face_size {
+                // Sample texel centers; faces meet exactly at their edges
+                let u = ((x as f32 + 0.5) / face_size as f32) * 2.0 - 1.0;
+                let v = 1.0 - ((y as f32 + 0.5) / face_size as f32) * 2.0;
+                let direction = cube_to_world((*forward + *right * u + *up * v).normalize());
+
+                let color = sample_bilinear(source, equirect_uv(direction));
+                let row = face_idx as u32 * face_size + y;
+                let _ = image.set_color_at(x, row, color);
+            }
+        }
+    }
+
+    image.reinterpret_stacked_2d_as_array(6);
+    image.texture_view_descriptor = Some(TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::Cube),
+        ..Default::default()
+    });
+    image
+}
+
+/// Bilinear sample of an image, wrapping horizontally and clamping vertically
+fn sample_bilinear(image: &Image, uv: Vec2) -> Color {
+    let width = image.width();
+    let height = image.height();
+    let x = uv.x * width as f32 - 0.5;
+    let y = (uv.y * height as f32 - 0.5).clamp(0.0, (height - 1) as f32);
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let tx = x - x0;
+    let ty = y - y0;
+
+    let texel = |x: f32, y: f32| {
+        let x = (x as i64).rem_euclid(width as i64) as u32;
+        let y = (y as u32).min(height - 1);
+        image.get_color_at(x, y).map(LinearRgba::from).unwrap_or(LinearRgba::BLACK)
+    };
+    let top = texel(x0, y0).mix(&texel(x0 + 1.0, y0), tx);
+    let bottom = texel(x0, y0 + 1.0).mix(&texel(x0 + 1.0, y0 + 1.0), tx);
+    top.mix(&bottom, ty).into()
+}
+
+/// Single texel per face cubemap in the background color of space
+fn skybox_placeholder(config: &SkyboxConfig) -> Image {
+    let background = srgb_bytes(config.background);
+    let mut image = Image::new_fill(
         Extent3d {
-            width: size,
-            height: total_height,
+            width: 1,
+            height: 6,
             depth_or_array_layers: 1,
         },
         TextureDimension::D2,
-        image_data,
+        &[background[0], background[1], background[2], 255],
         TextureFormat::Rgba8UnormSrgb,
         Default::default(),
     );
-    
-    // Reinterpret as cubemap array (this is the key!)
     image.reinterpret_stacked_2d_as_array(6);
-    image.texture_view_descriptor = Some(bevy::render::render_resource::TextureViewDescriptor {
+    image.texture_view_descriptor = Some(TextureViewDescriptor {
         dimension: Some(TextureViewDimension::Cube),
         ..Default::default()
     });
+    image
+}
+
+/// Renders a star catalog into a cubemap
+/// Each star is a soft point spread whose light follows its apparent magnitude,
+/// tinted by the blackbody color of its B-V index
+pub fn render_star_catalog(catalog: &StarCatalog, config: &SkyboxConfig) -> Image {
+    let size = config.resolution.max(2);
+    let mut canvas = CubemapCanvas::new(size, config.background);
+
+    // Spreads are sized for the reference face and scaled so stars cover the same angle at any resolution
+    let scale = size as f32 / STAR_REFERENCE_RESOLUTION as f32;
+    let min_sigma = (STAR_SIGMA * scale).max(0.6);
+    let mut drawn = 0;
+    for star in &catalog.stars {
+        if star.magnitude > config.catalog_magnitude_limit {
+            continue;
+        }
+        // Each magnitude is a factor of 10^0.4 in flux; stars at the limit get a faint dot
+        let flux = 10f32.powf(-0.4 * (star.magnitude - config.catalog_magnitude_limit));
+        let energy = CATALOG_LIMIT_ENERGY * flux * scale * scale;
+        let color = LinearRgba::from(star.color());
+        canvas.splat(star.direction(), Vec3::new(color.red, color.green, color.blue), energy, min_sigma);
+        drawn += 1;
+    }
+
+    info!("Rendered {} catalog stars into a {}x{} skybox", drawn, size, size);
+    canvas.into_image()
+}
+
+/// Creates a procedural space texture: seamless starfield, nebulae and galactic band
+///
+/// Stars are random points on the sphere splatted as soft point spreads, so the
+/// same seed gives the same sky at any resolution, without aliasing or seams.
+pub fn create_simple_space_skybox(config: &SkyboxConfig) -> Image {
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+    
+    let size = config.resolution.max(2);
+    info!("Generating high-res starfield skybox: {}x{} per face...", size, size);
+    
+    // Diffuse light from nebulae and the galactic band over the background
+    let mut canvas = CubemapCanvas::new(size, config.background);
+    let background = Vec3::new(config.background.0, config.background.1, config.background.2);
+    let glow = SkyGlow::new(&config.nebulae, config.galactic_band.as_ref(), config.seed);
+    canvas.paint(GLOW_DOWNSAMPLE, |direction| background + glow.sample(direction));
+    
+    // Star counts and spreads are defined at the reference resolution and
+    // scaled, so each star covers the same angle whatever the face size
+    let scale = size as f32 / STAR_REFERENCE_RESOLUTION as f32;
+    let reference_texels = 6.0 * (STAR_REFERENCE_RESOLUTION * STAR_REFERENCE_RESOLUTION) as f32;
+    let min_sigma = (STAR_SIGMA * scale).max(0.6);
+    let reference_area = std::f32::consts::TAU * STAR_SIGMA * STAR_SIGMA;
     
-    images.add(image)
+    // Tiers as (density, minimum and range of peak brightness out of 255)
+    let tiers = [
+        (config.bright_density, 100.0, 150.0),  // Bright stars (sparse)
+        (config.medium_density, 60.0, 100.0),   // Medium stars (more common)
+        (config.dim_density, 40.0, 80.0),       // Dim stars (even more common)
+    ];
+    
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let max_boost = config.galactic_band.as_ref().map_or(0.0, |band| band.star_boost.max(0.0));
+    for (density, min_brightness, brightness_range) in tiers {
+        // Oversample by the peak boost, then thin out stars away from the galactic plane
+        let candidates = (density.max(0.0) * reference_texels * (1.0 + max_boost)) as u32;
+        for _ in 0..candidates {
+            let direction = random_direction(&mut rng);
+            let boost = config
+                .galactic_band
+                .as_ref()
+                .map_or(1.0, |band| 1.0 + band.star_boost.max(0.0) * band.proximity(direction));
+            // Always draw the random numbers so acceptance doesn't shift later stars
+            let accept = rng.r#gen::<f32>() * (1.0 + max_boost) < boost;
+            let brightness = min_brightness + rng.r#gen::<f32>() * brightness_range;
+            let hue = rng.r#gen::<f32>();
+            if !accept {
+                continue;
+            }
+            
+            let brightness = brightness as u8;
+            let base = [brightness, brightness, brightness.saturating_add(15)];
+            let [r, g, b] = tint_star(base, hue, config.color_variation);
+            let color = srgb_to_linear(Vec3::new(r as f32, g as f32, b as f32) / 255.0);
+            
+            // Normalized color at full peak; energy keeps the old single-texel look at the reference size
+            let peak = color.max_element().max(1e-4);
+            canvas.splat(direction, color / peak, peak * reference_area * scale * scale, min_sigma);
+        }
+    }
+    
+    info!("Starfield generation complete!");
+    
+    canvas.into_image()
+}
+
+/// Uniformly distributed unit direction
+fn random_direction(rng: &mut impl rand::Rng) -> Vec3 {
+    let z: f32 = rng.gen_range(-1.0..=1.0);
+    let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+    let ring = (1.0 - z * z).max(0.0).sqrt();
+    Vec3::new(ring * angle.cos(), ring * angle.sin(), z)
 }
 
+/// Blends a star's color toward a blackbody tint picked by a random value in 0..1
+fn tint_star(base: [u8; 3], hue: f32, variation: f32) -> [u8; 3] {
+    if variation <= 0.0 {
+        return base;
+    }
+    // Spread temperatures from red dwarfs to blue giants
+    let temperature = 3_000.0 + hue * 9_000.0;
+    let tint = blackbody_color(temperature).to_srgba();
+    let luminance = base[0] as f32 / 255.0;
+    let variation = variation.clamp(0.0, 1.0);
+    let mix = |channel: u8, tint: f32| {
+        let plain = channel as f32 / 255.0;
+        ((plain + (luminance * tint - plain) * variation).clamp(0.0, 1.0) * 255.0) as u8
+    };
+    [mix(base[0], tint.red), mix(base[1], tint.green), mix(base[2], tint.blue)]
+}
+
+/// Converts an sRGB color to bytes
+fn srgb_bytes((r, g, b): (f32, f32, f32)) -> [u8; 3] {
+    let to_byte = |channel: f32| (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+    [to_byte(r), to_byte(g), to_byte(b)]
+}
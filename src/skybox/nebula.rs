@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+use serde::Serialize;
+use crate::noise::Perlin3;
+
+/// One layer of nebula clouds
+/// Layers are summed, so a faint wide layer under a bright patchy one reads as depth
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NebulaLayer {
+    /// Gradient of cloud colors (sRGB), picked by a second noise field
+    pub palette: Vec<(f32, f32, f32)>,
+    /// Feature size on the unit sphere; higher = smaller clouds
+    pub frequency: f32,
+    /// How far domain warping drags the clouds into filaments
+    pub warp: f32,
+    /// Fraction of the sky left empty (0 = everywhere, 1 = nowhere)
+    pub coverage: f32,
+    /// Peak brightness of the clouds
+    pub intensity: f32,
+    /// Noise octaves; more = finer wisps
+    pub octaves: u32,
+}
+
+impl NebulaLayer {
+    /// Broad, faint violet-blue haze
+    pub fn haze() -> Self {
+        Self {
+            palette: vec![(0.12, 0.08, 0.3), (0.25, 0.12, 0.45), (0.08, 0.18, 0.4)],
+            frequency: 1.2,
+            warp: 1.5,
+            coverage: 0.45,
+            intensity: 0.12,
+            octaves: 5,
+        }
+    }
+
+    /// Patchy emission clouds in red and orange
+    pub fn emission() -> Self {
+        Self {
+            palette: vec![(0.55, 0.1, 0.15), (0.75, 0.3, 0.15), (0.35, 0.05, 0.25)],
+            frequency: 2.5,
+            warp: 2.0,
+            coverage: 0.65,
+            intensity: 0.18,
+            octaves: 6,
+        }
+    }
+}
+
+/// Diffuse glow of the galactic plane, crossed by dark dust lanes
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GalacticBand {
+    /// Direction of the galactic pole; the band runs around the great circle facing it
+    pub pole: (f32, f32, f32),
+    /// Angular half-width of the band in radians
+    pub width: f32,
+    /// Color of the unresolved starlight (sRGB)
+    pub color: (f32, f32, f32),
+    /// Peak brightness at the center of the band
+    pub intensity: f32,
+    /// How much dust lanes darken the band (0 = none, 1 = fully black)
+    pub dust: f32,
+    /// Extra star density at the center of the band, as a multiple of the base density
+    pub star_boost: f32,
+}
+
+impl Default for GalacticBand {
+    fn default() -> Self {
+        Self {
+            // Tilted against the orbital plane, like the real Milky Way
+            pole: (0.3, 0.85, 0.45),
+            width: 0.18,
+            color: (0.55, 0.5, 0.45),
+            intensity: 0.16,
+            dust: 0.8,
+            star_boost: 3.0,
+        }
+    }
+}
+
+impl GalacticBand {
+    fn pole(&self) -> Vec3 {
+        Vec3::new(self.pole.0, self.pole.1, self.pole.2).normalize_or(Vec3::Y)
+    }
+
+    /// How close a direction is to the galactic plane (1 on it, falling to 0 away from it)
+    pub fn proximity(&self, direction: Vec3) -> f32 {
+        let latitude = direction.dot(self.pole()).clamp(-1.0, 1.0).asin();
+        let width = self.width.max(1e-3);
+        (-(latitude / width).powi(2)).exp()
+    }
+}
+
+/// Samples the diffuse light of the sky: nebula layers plus the galactic band
+/// Colors are sRGB and meant to be added on top of the background
+pub struct SkyGlow<'a> {
+    layers: &'a [NebulaLayer],
+    band: Option<&'a GalacticBand>,
+    density_noise: Vec<Perlin3>,
+    warp_noise: Vec<Perlin3>,
+    color_noise: Vec<Perlin3>,
+    dust_noise: Perlin3,
+}
+
+impl<'a> SkyGlow<'a> {
+    pub fn new(layers: &'a [NebulaLayer], band: Option<&'a GalacticBand>, seed: u64) -> Self {
+        // Separate seeds per layer and purpose so layers never line up
+        let noise = |offset: u64| Perlin3::new(seed.wrapping_add(offset));
+        Self {
+            layers,
+            band,
+            density_noise: (0..layers.len() as u64).map(|i| noise(100 + i * 10)).collect(),
+            warp_noise: (0..layers.len() as u64).map(|i| noise(101 + i * 10)).collect(),
+            color_noise: (0..layers.len() as u64).map(|i| noise(102 + i * 10)).collect(),
+            dust_noise: noise(3),
+        }
+    }
+
+    pub fn sample(&self, direction: Vec3) -> Vec3 {
+        let mut glow = Vec3::ZERO;
+
+        for (index, layer) in self.layers.iter().enumerate() {
+            let point = direction * layer.frequency;
+
+            // Domain warping: offset the lookup by another noise field for filaments and swirls
+            let warp_noise = &self.warp_noise[index];
+            let offset = Vec3::new(
+                warp_noise.fbm(point, 3),
+                warp_noise.fbm(point + Vec3::splat(17.3), 3),
+                warp_noise.fbm(point + Vec3::splat(-31.7), 3),
+            ) * layer.warp;
+            let noise = self.density_noise[index].fbm(point + offset, layer.octaves) * 0.5 + 0.5;
+
+            // Soft threshold leaves dark gaps between clouds
+            let density = smoothstep(layer.coverage, 1.0, noise);
+            if density <= 0.0 || layer.palette.is_empty() {
+                continue;
+            }
+
+            let tone = self.color_noise[index].fbm(point * 0.7 + offset, 3) * 0.5 + 0.5;
+            glow += palette_color(&layer.palette, tone) * density * layer.intensity;
+        }
+
+        if let Some(band) = self.band {
+            let proximity = band.proximity(direction);
+            if proximity > 1e-3 {
+                // Dust lanes hug the center of the band
+                let lanes = smoothstep(-0.1, 0.4, self.dust_noise.fbm(direction * 6.0, 5));
+                let core = proximity.powi(3);
+                let dust = (lanes * core * band.dust).clamp(0.0, 1.0);
+                let color = Vec3::new(band.color.0, band.color.1, band.color.2);
+                glow = glow * (1.0 - dust) + color * proximity * band.intensity * (1.0 - dust);
+            }
+        }
+
+        glow
+    }
+}
+
+/// Linearly interpolated color from a gradient palette
+fn palette_color(palette: &[(f32, f32, f32)], t: f32) -> Vec3 {
+    let position = t.clamp(0.0, 1.0) * (palette.len() - 1) as f32;
+    let index = (position as usize).min(palette.len() - 1);
+    let next = (index + 1).min(palette.len() - 1);
+    let (a, b) = (palette[index], palette[next]);
+    Vec3::new(a.0, a.1, a.2).lerp(Vec3::new(b.0, b.1, b.2), position - index as f32)
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
@@ -0,0 +1,196 @@
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureViewDescriptor, TextureViewDimension},
+};
+use super::{CUBE_FACES, cube_to_world};
+
+/// Widest point spread a single star may get, as a fraction of the face size
+/// Keeps absurd energies, like the Sun in a full catalog, from flooding whole faces
+const MAX_SIGMA_FRACTION: f32 = 0.02;
+
+/// Floating point cubemap that stars are drawn into as soft point spreads
+/// Colors are linear and accumulate, then get clamped to sRGB bytes at the end
+/// All directions are in world space, as the skybox shows them
+pub struct CubemapCanvas {
+    size: u32,
+    pixels: Vec<Vec3>,
+}
+
+impl CubemapCanvas {
+    /// Canvas filled with a background color (sRGB)
+    pub fn new(size: u32, background: (f32, f32, f32)) -> Self {
+        let size = size.max(2);
+        Self {
+            size,
+            pixels: vec![srgb_to_linear(Vec3::new(background.0, background.1, background.2)); (size * size * 6) as usize],
+        }
+    }
+
+    /// Direction through a point on a face, in texel units from its top-left corner
+    /// Texel `x` spans `x..x + 1`, so its center is at `x + 0.5` and the face
+    /// edges, shared with the neighbouring faces, are at 0 and `size`
+    fn direction_at(&self, face: usize, x: f32, y: f32) -> Vec3 {
+        let (_, forward, up, right) = CUBE_FACES[face];
+        let size = self.size as f32;
+        let u = x / size * 2.0 - 1.0;
+        let v = 1.0 - y / size * 2.0;
+        cube_to_world((forward + right * u + up * v).normalize())
+    }
+
+    /// Point on a face a direction passes through, in the units of `direction_at`
+    /// Possibly outside the face; `None` if the direction points away from it
+    fn project(&self, face: usize, direction: Vec3) -> Option<Vec2> {
+        let (_, forward, up, right) = CUBE_FACES[face];
+        let direction = cube_to_world(direction);
+        let depth = direction.dot(forward);
+        if depth <= 1e-4 {
+            return None;
+        }
+        let on_face = direction / depth;
+        let size = self.size as f32;
+        Some(Vec2::new(
+            (on_face.dot(right) + 1.0) * 0.5 * size,
+            (1.0 - on_face.dot(up)) * 0.5 * size,
+        ))
+    }
+
+    /// Replaces every texel with a smooth sky color (sRGB)
+    /// The sky is evaluated on a grid `downsample` times coarser than the faces and
+    /// interpolated at texel centers; grid edges sit on the face edges, so
+    /// neighbouring faces match up
+    pub fn paint(&mut self, downsample: u32, sky: impl Fn(Vec3) -> Vec3) {
+        let grid = (self.size / downsample.max(1)).max(1) + 1;
+        let grid_to_texel = self.size as f32 / (grid - 1) as f32;
+        let texel_to_grid = 1.0 / grid_to_texel;
+
+        for face in 0..6 {
+            let samples: Vec<Vec3> = (0..grid * grid)
+                .map(|i| {
+                    let x = (i % grid) as f32 * grid_to_texel;
+                    let y = (i / grid) as f32 * grid_to_texel;
+                    sky(self.direction_at(face, x, y))
+                })
+                .collect();
+            let at = |x: u32, y: u32| samples[(y * grid + x) as usize];
+
+            let face_offset = face * (self.size * self.size) as usize;
+            for y in 0..self.size {
+                for x in 0..self.size {
+                    let gx = (x as f32 + 0.5) * texel_to_grid;
+                    let gy = (y as f32 + 0.5) * texel_to_grid;
+                    let x0 = (gx as u32).min(grid - 2);
+                    let y0 = (gy as u32).min(grid - 2);
+                    let tx = gx - x0 as f32;
+                    let ty = gy - y0 as f32;
+                    let top = at(x0, y0).lerp(at(x0 + 1, y0), tx);
+                    let bottom = at(x0, y0 + 1).lerp(at(x0 + 1, y0 + 1), tx);
+                    self.pixels[face_offset + (y * self.size + x) as usize] = srgb_to_linear(top.lerp(bottom, ty));
+                }
+            }
+        }
+    }
+
+    /// Adds a Gaussian point spread centered on a direction
+    ///
+    /// `energy` is the total light of the star in texels at full brightness.
+    /// The spread never gets narrower than `min_sigma` texels, which keeps faint
+    /// stars anti-aliased; stars too bright for that width grow instead of clipping,
+    /// up to a small fraction of the face, beyond which their energy is capped.
+    /// Stars near an edge spill onto the neighbouring faces, so seams stay invisible.
+    pub fn splat(&mut self, direction: Vec3, color: Vec3, energy: f32, min_sigma: f32) {
+        let max_sigma = (self.size as f32 * MAX_SIGMA_FRACTION).max(min_sigma);
+        let energy = energy.min(std::f32::consts::TAU * max_sigma * max_sigma);
+        let area = std::f32::consts::TAU * min_sigma * min_sigma;
+        let (sigma, peak) = if energy <= area {
+            (min_sigma, energy / area)
+        } else {
+            ((energy / std::f32::consts::TAU).sqrt(), 1.0)
+        };
+        let radius = sigma * 3.0;
+        let falloff = -0.5 / (sigma * sigma);
+        let size = self.size as f32;
+        let last = (self.size - 1) as f32;
+        let direction = direction.normalize();
+
+        for face in 0..6 {
+            // Skip faces the spread can't reach
+            let Some(center) = self.project(face, direction) else {
+                continue;
+            };
+            let reach = radius - 0.5;
+            if center.min_element() < -reach || center.max_element() > size + reach {
+                continue;
+            }
+
+            // Texels whose centers are within reach, clamped to the face
+            let range = |center: f32| {
+                (center - radius - 0.5).max(0.0).ceil() as u32..=(center + radius - 0.5).min(last).floor() as u32
+            };
+            let face_offset = face * (self.size * self.size) as usize;
+            let x_range = range(center.x);
+            for y in range(center.y) {
+                for x in x_range.clone() {
+                    let texel_center = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                    let distance_squared = texel_center.distance_squared(center);
+                    let weight = peak * (distance_squared * falloff).exp();
+                    self.pixels[face_offset + (y * self.size + x) as usize] += color * weight;
+                }
+            }
+        }
+    }
+
+    /// Converts the canvas into a stacked sRGB cubemap image
+    pub fn into_image(self) -> Image {
+        let data = self
+            .pixels
+            .iter()
+            .flat_map(|pixel| {
+                let srgb = Color::linear_rgb(pixel.x, pixel.y, pixel.z).to_srgba();
+                let byte = |channel: f32| (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+                [byte(srgb.red), byte(srgb.green), byte(srgb.blue), 255]
+            })
+            .collect();
+
+        let mut image = Image::new(
+            Extent3d {
+                width: self.size,
+                height: self.size * 6,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            Default::default(),
+        );
+        image.reinterpret_stacked_2d_as_array(6);
+        image.texture_view_descriptor = Some(TextureViewDescriptor {
+            dimension: Some(TextureViewDimension::Cube),
+            ..Default::default()
+        });
+        image
+    }
+}
+
+/// Converts an sRGB color to linear
+pub fn srgb_to_linear(color: Vec3) -> Vec3 {
+    let linear = LinearRgba::from(Color::srgb(color.x, color.y, color.z));
+    Vec3::new(linear.red, linear.green, linear.blue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn texel_centers_round_trip() {
+        let canvas = CubemapCanvas::new(8, (0.0, 0.0, 0.0));
+        for face in 0..6 {
+            for (x, y) in [(0, 0), (3, 5), (7, 7)] {
+                let center = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let direction = canvas.direction_at(face, center.x, center.y);
+                let projected = canvas.project(face, direction).unwrap();
+                assert!(projected.distance(center) < 1e-4, "face {face} texel {x},{y}: {projected}");
+            }
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+    reflect::TypePath,
+};
+use crate::stellar::blackbody_color;
+
+/// Bundled catalog of the brightest stars (relative to `assets/`)
+pub const DEFAULT_STAR_CATALOG_PATH: &str = "catalogs/bright_stars.csv";
+
+/// A star from a catalog, in equatorial coordinates
+#[derive(Debug, Clone)]
+pub struct CatalogStar {
+    /// Right ascension in radians
+    pub right_ascension: f32,
+    /// Declination in radians
+    pub declination: f32,
+    /// Apparent visual magnitude (lower = brighter)
+    pub magnitude: f32,
+    /// B-V color index (negative = blue, ~0.65 = Sun, >1.5 = red)
+    pub color_index: f32,
+    /// Proper name, if it has one
+    pub name: Option<String>,
+    /// Constellation abbreviation, e.g. "Ori"
+    pub constellation: Option<String>,
+}
+
+impl CatalogStar {
+    /// Direction of the star on the sky, see `equatorial_direction`
+    pub fn direction(&self) -> Vec3 {
+        equatorial_direction(self.right_ascension, self.declination)
+    }
+
+    /// Blackbody color of the star
+    pub fn color(&self) -> Color {
+        blackbody_color(color_index_temperature(self.color_index))
+    }
+}
+
+/// Star catalog loaded from a CSV file
+#[derive(Asset, TypePath, Debug, Clone, Default)]
+pub struct StarCatalog {
+    pub stars: Vec<CatalogStar>,
+}
+
+/// Unit direction for equatorial coordinates (radians)
+/// The celestial north pole is +Y and right ascension 0 is +X, increasing
+/// counterclockwise seen from the north like the real sky
+pub fn equatorial_direction(right_ascension: f32, declination: f32) -> Vec3 {
+    Vec3::new(
+        declination.cos() * right_ascension.cos(),
+        declination.sin(),
+        -declination.cos() * right_ascension.sin(),
+    )
+}
+
+/// Effective temperature in kelvin from the B-V color index (Ballesteros 2012)
+pub fn color_index_temperature(color_index: f32) -> f32 {
+    let bv = color_index.clamp(-0.4, 2.0);
+    4600.0 * (1.0 / (0.92 * bv + 1.7) + 1.0 / (0.92 * bv + 0.62))
+}
+
+/// Loader for star catalog CSV files with a header row
+///
+/// Columns are found by name, so full HYG and Hipparcos exports load directly
+/// (HYG's first row is the Sun, which is skipped):
+/// - right ascension: `ra` (hours), `rarad` (radians) or `radeg`/`ra_deg` (degrees)
+/// - declination: `dec` (degrees), `decrad` (radians) or `dedeg`/`dec_deg` (degrees)
+/// - magnitude: `mag` or `vmag`
+/// - color index: `ci` or `b-v` (optional, defaults to Sun-like)
+/// - `proper` and `con` (optional) for names and constellations
+/// - `dist` (optional); rows at distance 0 are the Sun and are skipped
+#[derive(Default)]
+pub struct StarCatalogLoader;
+
+impl AssetLoader for StarCatalogLoader {
+    type Asset = StarCatalog;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        parse_catalog(&String::from_utf8(bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["csv"]
+    }
+}
+
+/// Parses catalog CSV text; rows with missing or invalid coordinates are skipped,
+/// as is the Sun, which would outshine the whole sky
+pub fn parse_catalog(text: &str) -> Result<StarCatalog, BevyError> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<String> = split_row(lines.next().ok_or("star catalog is empty")?)
+        .into_iter()
+        .map(|column| column.to_ascii_lowercase())
+        .collect();
+    let column = |names: &[&str]| header.iter().position(|column| names.contains(&column.as_str()));
+
+    // Angle columns as (index, factor converting to radians)
+    let right_ascension = column(&["rarad"])
+        .map(|index| (index, 1.0))
+        .or_else(|| column(&["ra"]).map(|index| (index, std::f32::consts::TAU / 24.0)))
+        .or_else(|| column(&["radeg", "ra_deg"]).map(|index| (index, 1f32.to_radians())))
+        .ok_or("star catalog has no right ascension column")?;
+    let declination = column(&["decrad"])
+        .map(|index| (index, 1.0))
+        .or_else(|| column(&["dec", "dedeg", "dec_deg"]).map(|index| (index, 1f32.to_radians())))
+        .ok_or("star catalog has no declination column")?;
+    let magnitude = column(&["mag", "vmag"]).ok_or("star catalog has no magnitude column")?;
+    let color_index = column(&["ci", "b-v", "bv"]);
+    let name = column(&["proper", "name"]);
+    let constellation = column(&["con", "constellation"]);
+    let distance = column(&["dist"]);
+
+    let mut stars = Vec::new();
+    for line in lines {
+        let fields = split_row(line);
+        let number = |index: usize| fields.get(index).and_then(|field| field.parse::<f32>().ok());
+        let label = |index: Option<usize>| {
+            index
+                .and_then(|index| fields.get(index))
+                .filter(|field| !field.is_empty())
+                .cloned()
+        };
+
+        let (Some(ra), Some(dec), Some(mag)) = (
+            number(right_ascension.0),
+            number(declination.0),
+            number(magnitude),
+        ) else {
+            continue;
+        };
+        if distance.and_then(number) == Some(0.0) || label(name).as_deref() == Some("Sol") {
+            continue;
+        }
+        stars.push(CatalogStar {
+            right_ascension: ra * right_ascension.1,
+            declination: dec * declination.1,
+            magnitude: mag,
+            color_index: color_index.and_then(number).unwrap_or(0.65),
+            name: label(name),
+            constellation: label(constellation),
+        });
+    }
+
+    info!("Loaded star catalog with {} stars", stars.len());
+    Ok(StarCatalog { stars })
+}
+
+/// Splits a CSV row, trimming whitespace around fields
+/// Quoted fields may contain commas and doubled `""` quotes, but not line breaks
+fn split_row(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-5, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn right_ascension_units() {
+        let hours = parse_catalog("ra,dec,mag\n6,45,1.0").unwrap();
+        let radians = parse_catalog("rarad,decrad,mag\n1.5707964,0.7853982,1.0").unwrap();
+        let degrees = parse_catalog("radeg,dedeg,mag\n90,45,1.0").unwrap();
+        for catalog in [hours, radians, degrees] {
+            assert_eq!(catalog.stars.len(), 1);
+            assert_close(catalog.stars[0].right_ascension, std::f32::consts::FRAC_PI_2);
+            assert_close(catalog.stars[0].declination, std::f32::consts::FRAC_PI_4);
+        }
+    }
+
+    #[test]
+    fn skips_the_sun() {
+        let catalog = parse_catalog(
+            "proper,ra,dec,mag,dist\n\
+             Sol,0,0,-26.7,0\n\
+             Sol,1,1,-26.7,\n\
+             Unnamed,2,2,-26.7,0\n\
+             Sirius,6.75,-16.7,-1.44,2.64",
+        )
+        .unwrap();
+        let names: Vec<_> = catalog.stars.iter().map(|star| star.name.as_deref()).collect();
+        assert_eq!(names, [Some("Sirius")]);
+    }
+
+    #[test]
+    fn skips_invalid_rows() {
+        let catalog = parse_catalog(
+            "ra,dec,mag\n\
+             1,2,3\n\
+             ,2,3\n\
+             1,north,3\n\
+             1,2\n\
+             \n\
+             4,5,6",
+        )
+        .unwrap();
+        let magnitudes: Vec<_> = catalog.stars.iter().map(|star| star.magnitude).collect();
+        assert_eq!(magnitudes, [3.0, 6.0]);
+    }
+
+    #[test]
+    fn missing_magnitude_column_is_an_error() {
+        assert!(parse_catalog("ra,dec,ci\n1,2,0.5").is_err());
+        assert!(parse_catalog("").is_err());
+    }
+
+    #[test]
+    fn quoted_fields_may_contain_commas_and_quotes() {
+        let catalog = parse_catalog(
+            "\"proper\",\"ra\",\"dec\",\"mag\"\n\
+             \"Alpha, \"\"the first\"\"\",1,2,3",
+        )
+        .unwrap();
+        assert_eq!(catalog.stars[0].name.as_deref(), Some("Alpha, \"the first\""));
+        assert_eq!(catalog.stars[0].magnitude, 3.0);
+    }
+}
@@ -0,0 +1,192 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+    reflect::TypePath,
+};
+use serde::{Deserialize, Serialize};
+use crate::atmosphere::AtmosphereDefinition;
+use crate::gas_giant_textures::GasGiantParams;
+use crate::stellar::StarDescriptor;
+use crate::system_generator::{SEED_ENV_VAR, activate_generated_system};
+
+/// Path of the star system loaded at startup (relative to `assets/`)
+pub const DEFAULT_STAR_SYSTEM_PATH: &str = "systems/home.system.ron";
+
+/// An sRGB color as `(r, g, b)`; values above 1.0 are allowed for emissive glow
+pub type Rgb = (f32, f32, f32);
+
+/// Complete description of a star system, loaded from a `.system.ron` file
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct StarSystemDefinition {
+    /// Display name of the system
+    pub name: String,
+    /// The central star
+    pub star: StarDefinition,
+    /// Planets, moons and comets; parents must be listed by name
+    #[serde(default)]
+    pub bodies: Vec<BodyDefinition>,
+}
+
+/// The central star, which is also the system's only light source
+/// Its color, size, light and mass are all derived from the descriptor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarDefinition {
+    pub descriptor: StarDescriptor,
+}
+
+/// A planet, moon or other orbiting body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BodyDefinition {
+    /// Unique name, used by other bodies to reference this one as a parent
+    pub name: String,
+    pub radius: f32,
+    pub material: MaterialDefinition,
+    pub orbit: OrbitDefinition,
+    /// Name of the body this one orbits (the star when omitted)
+    #[serde(default)]
+    pub parent: Option<String>,
+    #[serde(default)]
+    pub mass: f32,
+    #[serde(default)]
+    pub rotation: Option<RotationDefinition>,
+    /// Color of the orbit line (a neutral grey when omitted)
+    #[serde(default)]
+    pub orbit_color: Option<Rgb>,
+    /// Marks the body the camera starts on
+    #[serde(default)]
+    pub home: bool,
+    /// Optional planetary ring, drawn in the body's equatorial plane
+    #[serde(default)]
+    pub rings: Option<RingDefinition>,
+}
+
+/// A flat planetary ring
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RingDefinition {
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub color: Rgb,
+    /// 0.0 (invisible) to 1.0 (solid)
+    pub opacity: f32,
+}
+
+/// Surface appearance of a body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MaterialDefinition {
+    /// Matte single-color surface
+    Rocky {
+        color: Rgb,
+        #[serde(default)]
+        emissive: Rgb,
+    },
+    /// Banded gas giant with drifting clouds
+    GasGiant {
+        texture: TextureGenerator,
+        #[serde(default)]
+        emissive: Rgb,
+        #[serde(default)]
+        atmosphere: AtmosphereDefinition,
+    },
+}
+
+/// Texture for a gas giant: a named preset, custom parameters or a baked image
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TextureGenerator {
+    AmberTitan,
+    AzureColossus,
+    Custom(GasGiantParams),
+    /// Equirectangular image relative to `assets/`, e.g. one written by `bake_textures`
+    Asset(String),
+}
+
+impl TextureGenerator {
+    /// Generator parameters for this texture, or `None` for baked images
+    pub fn params(&self) -> Option<GasGiantParams> {
+        match self {
+            TextureGenerator::AmberTitan => Some(GasGiantParams::amber_titan()),
+            TextureGenerator::AzureColossus => Some(GasGiantParams::azure_colossus()),
+            TextureGenerator::Custom(params) => Some(params.clone()),
+            TextureGenerator::Asset(_) => None,
+        }
+    }
+}
+
+/// Keplerian elements of an orbit (see `OrbitalBody`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrbitDefinition {
+    pub semi_major_axis: f32,
+    #[serde(default)]
+    pub eccentricity: f32,
+    #[serde(default)]
+    pub inclination: f32,
+    #[serde(default)]
+    pub longitude_of_ascending_node: f32,
+    #[serde(default)]
+    pub argument_of_periapsis: f32,
+    #[serde(default)]
+    pub mean_anomaly_at_epoch: f32,
+    /// Radians per simulated second; derived from the central mass when left out
+    #[serde(default)]
+    pub mean_motion: Option<f32>,
+}
+
+/// Spin of a body (see `AxialRotation`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotationDefinition {
+    #[serde(default)]
+    pub sidereal_period: f32,
+    #[serde(default)]
+    pub axial_tilt: f32,
+    #[serde(default)]
+    pub tidally_locked: bool,
+}
+
+/// Loader for `.system.ron` star system files
+#[derive(Default)]
+pub struct StarSystemLoader;
+
+impl AssetLoader for StarSystemLoader {
+    type Asset = StarSystemDefinition;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        // Implicit Some keeps files written as `mean_motion: 0.3` loading
+        let options = ron::Options::default().with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME);
+        Ok(options.from_bytes::<StarSystemDefinition>(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["system.ron"]
+    }
+}
+
+/// Resource holding the star system currently shown in the scene
+#[derive(Resource)]
+pub struct ActiveStarSystem(pub Handle<StarSystemDefinition>);
+
+/// Marker for every entity spawned from a star system definition
+/// These are despawned and rebuilt when the definition is hot-reloaded
+#[derive(Component)]
+pub struct StarSystemMember;
+
+/// System that starts loading the default star system
+/// Setting `STAR_SYSTEM_SEED` generates that system instead of loading the file
+pub fn load_star_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut definitions: ResMut<Assets<StarSystemDefinition>>,
+) {
+    if let Some(seed) = std::env::var(SEED_ENV_VAR).ok().and_then(|value| value.parse().ok()) {
+        activate_generated_system(&mut commands, &mut definitions, seed);
+        return;
+    }
+    commands.insert_resource(ActiveStarSystem(asset_server.load(DEFAULT_STAR_SYSTEM_PATH)));
+}
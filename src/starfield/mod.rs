@@ -1,40 +1,209 @@
-use bevy::prelude::*;
-use rand::Rng;
+use bevy::{
+    asset::RenderAssetUsages,
+    camera::visibility::NoFrustumCulling,
+    mesh::{Indices, MeshVertexAttribute, MeshVertexBufferLayoutRef},
+    pbr::{MaterialPipeline, MaterialPipelineKey},
+    prelude::*,
+    render::render_resource::{
+        AsBindGroup, PrimitiveTopology, RenderPipelineDescriptor, ShaderType,
+        SpecializedMeshPipelineError, VertexFormat,
+    },
+    shader::ShaderRef,
+};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use crate::stellar::blackbody_color;
 
-/// System that spawns a starfield background
+/// Shader that draws stars as twinkling point sprites
+const SHADER_ASSET_PATH: &str = "shaders/starfield.wgsl";
+
+/// Per-vertex star data: quad corner (xy), size scale and twinkle phase
+pub const ATTRIBUTE_STAR: MeshVertexAttribute =
+    MeshVertexAttribute::new("Star", 731_904_225, VertexFormat::Float32x4);
+
+/// Resource describing the dynamic starfield drawn on top of the skybox
+#[derive(Resource, Clone)]
+pub struct StarfieldConfig {
+    pub seed: u64,
+    /// Stars on a sphere that moves with the camera, like the skybox
+    pub distant_count: usize,
+    /// Radius of the distant sphere; must be beyond every body in the system
+    pub distant_radius: f32,
+    /// Stars fixed in the world, which shift against the distant ones as the camera moves
+    pub near_count: usize,
+    /// Inner and outer radius of the shell the near stars are scattered in
+    pub near_radius: (f32, f32),
+    /// Overall star brightness
+    pub brightness: f32,
+    /// How much stars dim at the bottom of a twinkle (0 = steady, 1 = fully out)
+    pub twinkle: f32,
+    /// Angular half-width of a star quad in radians
+    pub star_size: f32,
+}
+
+impl Default for StarfieldConfig {
+    fn default() -> Self {
+        Self {
+            seed: 7,
+            distant_count: 40_000,
+            distant_radius: 800.0,
+            near_count: 3_000,
+            near_radius: (150.0, 500.0),
+            brightness: 1.5,
+            twinkle: 0.6,
+            star_size: 0.0025,
+        }
+    }
+}
+
+/// Uniform data for the starfield shader
+#[derive(Debug, Clone, Copy, Default, ShaderType, Reflect)]
+pub struct StarfieldSettings {
+    pub brightness: f32,
+    pub twinkle: f32,
+    pub star_size: f32,
+}
+
+/// Additive point-sprite material; each star is a camera-facing quad
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone, Default)]
+pub struct StarfieldMaterial {
+    #[uniform(0)]
+    pub settings: StarfieldSettings,
+}
+
+impl Material for StarfieldMaterial {
+    fn vertex_shader() -> ShaderRef {
+        SHADER_ASSET_PATH.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        SHADER_ASSET_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Add
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let vertex_layout = layout.0.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            Mesh::ATTRIBUTE_COLOR.at_shader_location(1),
+            ATTRIBUTE_STAR.at_shader_location(2),
+        ])?;
+        descriptor.vertex.buffers = vec![vertex_layout];
+        Ok(())
+    }
+}
+
+/// Starfield layer that stays centered on the camera, so it shows no parallax
+#[derive(Component)]
+pub struct DistantStarLayer;
+
+/// System that spawns the starfield as two meshes, one draw call each
+/// The distant layer follows the camera; the near layer stays put, giving parallax
 pub fn spawn_starfield(
     mut commands: Commands,
+    config: Res<StarfieldConfig>,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut materials: ResMut<Assets<StarfieldMaterial>>,
+) {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let material = materials.add(StarfieldMaterial {
+        settings: StarfieldSettings {
+            brightness: config.brightness,
+            twinkle: config.twinkle,
+            star_size: config.star_size,
+        },
+    });
+
+    let distant: Vec<Vec3> = (0..config.distant_count)
+        .map(|_| random_direction(&mut rng) * config.distant_radius)
+        .collect();
+    commands.spawn((
+        Mesh3d(meshes.add(star_mesh(&distant, &mut rng))),
+        MeshMaterial3d(material.clone()),
+        Transform::default(),
+        // Quads grow in the vertex shader, past the bounds of the mesh
+        NoFrustumCulling,
+        DistantStarLayer,
+    ));
+
+    // Uniform in volume, so the shell isn't crowded at its inner edge
+    let (inner, outer) = config.near_radius;
+    let near: Vec<Vec3> = (0..config.near_count)
+        .map(|_| {
+            let t = rng.r#gen::<f32>();
+            let radius = (inner.powi(3) + t * (outer.powi(3) - inner.powi(3))).cbrt();
+            random_direction(&mut rng) * radius
+        })
+        .collect();
+    commands.spawn((
+        Mesh3d(meshes.add(star_mesh(&near, &mut rng))),
+        MeshMaterial3d(material),
+        Transform::default(),
+        NoFrustumCulling,
+    ));
+
+    info!("Spawned starfield with {} distant and {} near stars", distant.len(), near.len());
+}
+
+/// System that keeps the distant star layer centered on the camera
+/// Runs before transform propagation; the camera is a root entity, so its Transform is global
+pub fn follow_camera_starfield(
+    cameras: Query<&Transform, (With<Camera3d>, Without<DistantStarLayer>)>,
+    mut layers: Query<&mut Transform, With<DistantStarLayer>>,
 ) {
-    let mut rng = rand::thread_rng();
-    let starfield_radius = 200.0;
-    let star_count = 500;
-    
-    for _ in 0..star_count {
-        // Generate random point on a sphere surface
-        let theta = rng.gen_range(0.0..std::f32::consts::TAU);
-        let phi = rng.gen_range(0.0..std::f32::consts::PI);
-        
-        let x = starfield_radius * phi.sin() * theta.cos();
-        let y = starfield_radius * phi.sin() * theta.sin();
-        let z = starfield_radius * phi.cos();
-        
-        // Random star size (very small)
-        let size = rng.gen_range(0.05..0.15);
-        
-        // Random star color (white to slight blue tint)
-        let color_intensity = rng.gen_range(0.8..1.0);
-        let blue_tint = rng.gen_range(0.9..1.0);
-        
-        commands.spawn((
-            Mesh3d(meshes.add(Sphere::new(size))),
-            MeshMaterial3d(materials.add(StandardMaterial {
-                emissive: Color::srgb(color_intensity, color_intensity, blue_tint).into(),
-                ..default()
-            })),
-            Transform::from_xyz(x, y, z),
-        ));
+    let Some(camera) = cameras.iter().next() else {
+        return;
+    };
+    for mut transform in layers.iter_mut() {
+        transform.translation = camera.translation;
     }
 }
 
+/// Builds one mesh holding a quad per star
+/// All four corners share the star's position; the shader spreads them out on screen
+fn star_mesh(positions: &[Vec3], rng: &mut StdRng) -> Mesh {
+    const CORNERS: [Vec2; 4] = [Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0)];
+
+    let mut vertex_positions = Vec::with_capacity(positions.len() * 4);
+    let mut colors = Vec::with_capacity(positions.len() * 4);
+    let mut stars = Vec::with_capacity(positions.len() * 4);
+    let mut indices = Vec::with_capacity(positions.len() * 6);
+
+    for (index, position) in positions.iter().enumerate() {
+        // Most stars are faint; a power curve gives a few bright ones
+        let brightness = 0.15 + 0.85 * rng.r#gen::<f32>().powi(4);
+        let temperature = rng.gen_range(3_000.0..12_000.0);
+        let color = LinearRgba::from(blackbody_color(temperature)) * brightness;
+        let size = 0.6 + brightness;
+        let phase = rng.gen_range(0.0..std::f32::consts::TAU);
+
+        for corner in CORNERS {
+            vertex_positions.push(position.to_array());
+            colors.push([color.red, color.green, color.blue, 1.0]);
+            stars.push([corner.x, corner.y, size, phase]);
+        }
+        let base = index as u32 * 4;
+        indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::RENDER_WORLD)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vertex_positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+        .with_inserted_attribute(ATTRIBUTE_STAR, stars)
+        .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Uniformly distributed unit vector
+fn random_direction(rng: &mut impl Rng) -> Vec3 {
+    let z: f32 = rng.gen_range(-1.0..1.0);
+    let theta = rng.gen_range(0.0..std::f32::consts::TAU);
+    let r = (1.0 - z * z).sqrt();
+    Vec3::new(r * theta.cos(), r * theta.sin(), z)
+}
@@ -0,0 +1,235 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Effective temperature of the Sun in kelvin, the reference for scaling
+const SOLAR_TEMPERATURE: f32 = 5778.0;
+
+// Scene values for a Sun-like star (G class, one solar luminosity)
+const SOLAR_VISUAL_RADIUS: f32 = 8.0;
+const SOLAR_LIGHT_INTENSITY: f32 = 2_000_000.0;
+const SOLAR_LIGHT_RANGE: f32 = 250.0;
+const SOLAR_EMISSIVE_STRENGTH: f32 = 50.0;
+const SOLAR_MASS: f32 = 900.0;
+
+/// Harvard spectral classes of main-sequence stars
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SpectralClass {
+    O,
+    B,
+    A,
+    F,
+    G,
+    K,
+    M,
+}
+
+impl SpectralClass {
+    /// All classes from hottest to coolest
+    pub const ALL: [SpectralClass; 7] = [
+        SpectralClass::O,
+        SpectralClass::B,
+        SpectralClass::A,
+        SpectralClass::F,
+        SpectralClass::G,
+        SpectralClass::K,
+        SpectralClass::M,
+    ];
+
+    /// Typical effective temperature in kelvin
+    pub fn temperature(self) -> f32 {
+        match self {
+            SpectralClass::O => 35_000.0,
+            SpectralClass::B => 15_000.0,
+            SpectralClass::A => 8_500.0,
+            SpectralClass::F => 6_500.0,
+            SpectralClass::G => SOLAR_TEMPERATURE,
+            SpectralClass::K => 4_500.0,
+            SpectralClass::M => 3_200.0,
+        }
+    }
+
+    /// Typical luminosity in solar units
+    pub fn luminosity(self) -> f32 {
+        match self {
+            SpectralClass::O => 100_000.0,
+            SpectralClass::B => 1_000.0,
+            SpectralClass::A => 20.0,
+            SpectralClass::F => 2.5,
+            SpectralClass::G => 1.0,
+            SpectralClass::K => 0.3,
+            SpectralClass::M => 0.02,
+        }
+    }
+
+    /// Class letter, e.g. 'G'
+    pub fn letter(self) -> char {
+        match self {
+            SpectralClass::O => 'O',
+            SpectralClass::B => 'B',
+            SpectralClass::A => 'A',
+            SpectralClass::F => 'F',
+            SpectralClass::G => 'G',
+            SpectralClass::K => 'K',
+            SpectralClass::M => 'M',
+        }
+    }
+}
+
+/// Physical description of a star from which its appearance and light are derived
+///
+/// Real stars span many orders of magnitude in luminosity, so scene values are
+/// compressed with fractional powers around the `SOLAR_*` scene constants, so
+/// red dwarfs and blue giants stay within a range the renderer and camera can
+/// handle. A Sun-like star keeps the original radius, light intensity and
+/// range, but its color and emissive come from its blackbody temperature,
+/// which is whiter than the original hand-picked yellow.
+#[derive(Component, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum StarDescriptor {
+    /// Typical main-sequence star of a spectral class
+    Class(SpectralClass),
+    /// Explicit effective temperature (kelvin) and luminosity (solar units)
+    Physical { temperature: f32, luminosity: f32 },
+}
+
+impl StarDescriptor {
+    /// Effective temperature in kelvin
+    pub fn temperature(&self) -> f32 {
+        match *self {
+            StarDescriptor::Class(class) => class.temperature(),
+            StarDescriptor::Physical { temperature, .. } => temperature,
+        }
+    }
+
+    /// Luminosity in solar units
+    pub fn luminosity(&self) -> f32 {
+        match *self {
+            StarDescriptor::Class(class) => class.luminosity(),
+            StarDescriptor::Physical { luminosity, .. } => luminosity.max(1e-4),
+        }
+    }
+
+    /// Spectral class matching the temperature
+    pub fn spectral_class(&self) -> SpectralClass {
+        match *self {
+            StarDescriptor::Class(class) => class,
+            StarDescriptor::Physical { temperature, .. } => match temperature {
+                t if t >= 30_000.0 => SpectralClass::O,
+                t if t >= 10_000.0 => SpectralClass::B,
+                t if t >= 7_500.0 => SpectralClass::A,
+                t if t >= 6_000.0 => SpectralClass::F,
+                t if t >= 5_200.0 => SpectralClass::G,
+                t if t >= 3_700.0 => SpectralClass::K,
+                _ => SpectralClass::M,
+            },
+        }
+    }
+
+    /// Color of the star's light, from its blackbody temperature
+    pub fn color(&self) -> Color {
+        blackbody_color(self.temperature())
+    }
+
+    /// Radius in solar radii from the Stefan-Boltzmann law (L ~ R^2 * T^4)
+    pub fn physical_radius(&self) -> f32 {
+        let temperature_ratio = SOLAR_TEMPERATURE / self.temperature();
+        self.luminosity().sqrt() * temperature_ratio * temperature_ratio
+    }
+
+    /// Radius of the star's sphere in the scene
+    pub fn visual_radius(&self) -> f32 {
+        (SOLAR_VISUAL_RADIUS * self.physical_radius().powf(0.35)).clamp(3.0, 20.0)
+    }
+
+    /// Emissive color (HDR) of the star's surface
+    pub fn emissive(&self) -> LinearRgba {
+        let strength = SOLAR_EMISSIVE_STRENGTH * self.luminosity().powf(0.15);
+        (LinearRgba::from(self.color()) * strength).with_alpha(1.0)
+    }
+
+    /// Intensity of the star's point light
+    pub fn light_intensity(&self) -> f32 {
+        SOLAR_LIGHT_INTENSITY * self.luminosity().sqrt()
+    }
+
+    /// Range of the star's point light
+    /// Follows the inverse-square law, so it grows with the square root of intensity
+    pub fn light_range(&self) -> f32 {
+        SOLAR_LIGHT_RANGE * self.luminosity().powf(0.25)
+    }
+
+    /// Gravitational mass in scene units (see `Mass`)
+    /// Main-sequence mass follows L ~ M^3.5, further compressed for playable orbits
+    pub fn mass(&self) -> f32 {
+        SOLAR_MASS * self.luminosity().powf(1.0 / 7.0)
+    }
+}
+
+/// Approximate sRGB color of a blackbody at the given temperature (kelvin)
+/// Uses Tanner Helland's fit to the CIE color matching functions
+pub fn blackbody_color(temperature: f32) -> Color {
+    let t = temperature.clamp(1_000.0, 40_000.0) / 100.0;
+
+    let r = if t <= 66.0 {
+        255.0
+    } else {
+        329.698_73 * (t - 60.0).powf(-0.133_204_76)
+    };
+    let g = if t <= 66.0 {
+        99.470_8 * t.ln() - 161.119_57
+    } else {
+        288.122_17 * (t - 60.0).powf(-0.075_514_85)
+    };
+    let b = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.517_73 * (t - 10.0).ln() - 305.044_8
+    };
+
+    Color::srgb(
+        (r / 255.0).clamp(0.0, 1.0),
+        (g / 255.0).clamp(0.0, 1.0),
+        (b / 255.0).clamp(0.0, 1.0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class_at(temperature: f32) -> SpectralClass {
+        StarDescriptor::Physical { temperature, luminosity: 1.0 }.spectral_class()
+    }
+
+    #[test]
+    fn spectral_class_boundaries() {
+        let boundaries = [
+            (30_000.0, SpectralClass::O, SpectralClass::B),
+            (10_000.0, SpectralClass::B, SpectralClass::A),
+            (7_500.0, SpectralClass::A, SpectralClass::F),
+            (6_000.0, SpectralClass::F, SpectralClass::G),
+            (5_200.0, SpectralClass::G, SpectralClass::K),
+            (3_700.0, SpectralClass::K, SpectralClass::M),
+        ];
+        for (temperature, at, below) in boundaries {
+            assert_eq!(class_at(temperature), at, "at {temperature} K");
+            assert_eq!(class_at(temperature - 1.0), below, "below {temperature} K");
+        }
+    }
+
+    #[test]
+    fn typical_temperatures_fall_in_their_class() {
+        for class in SpectralClass::ALL {
+            assert_eq!(class_at(class.temperature()), class);
+        }
+    }
+
+    #[test]
+    fn sun_is_a_warm_white() {
+        let color = blackbody_color(SOLAR_TEMPERATURE).to_srgba();
+        assert_eq!(color.red, 1.0);
+        assert!((color.green - 0.95).abs() < 0.01, "green {}", color.green);
+        assert!((color.blue - 0.90).abs() < 0.01, "blue {}", color.blue);
+    }
+}
@@ -0,0 +1,242 @@
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use crate::atmosphere::AtmosphereDefinition;
+use crate::gas_giant_textures::GasGiantParams;
+use crate::star_system::{
+    ActiveStarSystem, BodyDefinition, MaterialDefinition, OrbitDefinition, Rgb, RingDefinition,
+    RotationDefinition, StarDefinition, StarSystemDefinition, TextureGenerator,
+};
+use crate::stellar::{SpectralClass, StarDescriptor};
+
+/// Environment variable selecting a generated system at startup instead of the RON file
+pub const SEED_ENV_VAR: &str = "STAR_SYSTEM_SEED";
+
+/// Relative frequency of each spectral class, favoring common small stars
+const CLASS_WEIGHTS: [(SpectralClass, u32); 7] = [
+    (SpectralClass::O, 1),
+    (SpectralClass::B, 2),
+    (SpectralClass::A, 4),
+    (SpectralClass::F, 8),
+    (SpectralClass::G, 12),
+    (SpectralClass::K, 14),
+    (SpectralClass::M, 20),
+];
+
+/// Palette for rocky worlds
+const ROCKY_COLORS: [Rgb; 8] = [
+    (0.3, 0.6, 0.95),
+    (0.95, 0.35, 0.25),
+    (0.75, 0.4, 0.85),
+    (0.6, 0.58, 0.55),
+    (0.55, 0.75, 0.4),
+    (0.85, 0.7, 0.45),
+    (0.4, 0.45, 0.5),
+    (0.9, 0.9, 0.85),
+];
+
+/// Builds a plausible star system from a seed
+///
+/// The result is fully deterministic per seed: planets are spaced with a
+/// Titius-Bode-like geometric progression, rocky worlds sit inside the frost
+/// line and gas giants beyond it, and gas giants may carry moons and rings.
+pub fn generate_star_system(seed: u64) -> StarSystemDefinition {
+    // ChaCha8's stream is fixed across platforms, unlike StdRng's; the values
+    // sampled from it are only stable for a given rand version
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    // Pick a spectral class, weighted toward common small stars
+    let total_weight: u32 = CLASS_WEIGHTS.iter().map(|(_, weight)| weight).sum();
+    let mut pick = rng.gen_range(0..total_weight);
+    let class = CLASS_WEIGHTS
+        .iter()
+        .find(|(_, weight)| {
+            if pick < *weight {
+                true
+            } else {
+                pick -= weight;
+                false
+            }
+        })
+        .map(|(class, _)| *class)
+        .unwrap_or(SpectralClass::G);
+
+    // Brighter stars are bigger, heavier and push the frost line outward
+    let descriptor = StarDescriptor::Class(class);
+    let star_radius = descriptor.visual_radius();
+    let frost_line = 32.0 * descriptor.luminosity().powf(0.25);
+    let star = StarDefinition { descriptor };
+
+    let mut bodies = Vec::new();
+    let planet_count = rng.gen_range(2..=7);
+    let spacing_ratio = rng.gen_range(1.3..1.6);
+    let mut semi_major_axis = star_radius * 2.0 + rng.gen_range(6.0..10.0);
+
+    for index in 0..planet_count {
+        let name = format!("{}-{}", class.letter(), index + 1);
+        let gas_giant = semi_major_axis > frost_line && rng.gen_bool(0.75);
+
+        let radius = if gas_giant { rng.gen_range(3.5..6.0) } else { rng.gen_range(1.0..2.8) };
+        let mass = if gas_giant { rng.gen_range(150.0..300.0) } else { rng.gen_range(0.3..2.0) };
+
+        let (material, orbit_color) = if gas_giant {
+            let (texture, params) = if rng.gen_bool(0.5) {
+                (TextureGenerator::AmberTitan, GasGiantParams::amber_titan())
+            } else {
+                (TextureGenerator::AzureColossus, GasGiantParams::azure_colossus())
+            };
+            // Orbit line and rings take the palette's dominant color
+            let color = params.palette[0];
+            let emissive = (color.0 * 0.06, color.1 * 0.06, color.2 * 0.06);
+            // Vary cloud drift so neighbouring giants don't move in lockstep
+            let atmosphere = AtmosphereDefinition {
+                equator_speed: rng.gen_range(0.002..0.006),
+                polar_speed: rng.gen_range(-0.003..0.0),
+                ..default()
+            };
+            (MaterialDefinition::GasGiant { texture, emissive, atmosphere }, color)
+        } else {
+            let color = ROCKY_COLORS[rng.gen_range(0..ROCKY_COLORS.len())];
+            (MaterialDefinition::Rocky { color, emissive: (0.0, 0.0, 0.0) }, color)
+        };
+
+        let rings = (gas_giant && rng.gen_bool(0.4)).then(|| RingDefinition {
+            inner_radius: radius * rng.gen_range(1.3..1.6),
+            outer_radius: radius * rng.gen_range(1.9..2.5),
+            color: (orbit_color.0 * 0.9, orbit_color.1 * 0.9, orbit_color.2 * 0.85),
+            opacity: rng.gen_range(0.4..0.8),
+        });
+
+        bodies.push(BodyDefinition {
+            name: name.clone(),
+            radius,
+            material,
+            orbit: OrbitDefinition {
+                semi_major_axis,
+                eccentricity: rng.gen_range(0.0..0.08),
+                inclination: rng.gen_range(0.0..0.06),
+                longitude_of_ascending_node: rng.gen_range(0.0..std::f32::consts::TAU),
+                argument_of_periapsis: rng.gen_range(0.0..std::f32::consts::TAU),
+                mean_anomaly_at_epoch: rng.gen_range(0.0..std::f32::consts::TAU),
+                // Derived from the star's mass when spawned
+                mean_motion: None,
+            },
+            parent: None,
+            mass,
+            rotation: Some(RotationDefinition {
+                sidereal_period: rng.gen_range(3.0..15.0) * if rng.gen_bool(0.15) { -1.0 } else { 1.0 },
+                axial_tilt: rng.gen_range(0.0..0.5),
+                tidally_locked: false,
+            }),
+            orbit_color: Some(orbit_color),
+            home: false,
+            rings,
+        });
+
+        // Gas giants gather several moons, rocky planets rarely keep one
+        let moon_count = if gas_giant {
+            rng.gen_range(0..=4)
+        } else if rng.gen_bool(0.25) {
+            1
+        } else {
+            0
+        };
+        let mut moon_distance = radius * 2.5;
+        for moon_index in 0..moon_count {
+            moon_distance += rng.gen_range(1.2..2.5);
+            let color = ROCKY_COLORS[rng.gen_range(0..ROCKY_COLORS.len())];
+            bodies.push(BodyDefinition {
+                name: format!("{}{}", name, (b'a' + moon_index as u8) as char),
+                radius: rng.gen_range(0.3..0.8),
+                material: MaterialDefinition::Rocky { color, emissive: (0.0, 0.0, 0.0) },
+                orbit: OrbitDefinition {
+                    semi_major_axis: moon_distance,
+                    eccentricity: rng.gen_range(0.0..0.05),
+                    inclination: rng.gen_range(0.0..0.3),
+                    longitude_of_ascending_node: rng.gen_range(0.0..std::f32::consts::TAU),
+                    argument_of_periapsis: 0.0,
+                    mean_anomaly_at_epoch: rng.gen_range(0.0..std::f32::consts::TAU),
+                    mean_motion: None,
+                },
+                parent: Some(name.clone()),
+                mass: rng.gen_range(0.01..0.1),
+                rotation: Some(RotationDefinition {
+                    sidereal_period: 0.0,
+                    axial_tilt: 0.0,
+                    tidally_locked: true,
+                }),
+                orbit_color: None,
+                home: false,
+                rings: None,
+            });
+        }
+
+        // Leave room for the next planet and its moons
+        semi_major_axis = semi_major_axis * spacing_ratio + moon_distance.max(radius);
+    }
+
+    // The innermost rocky world is where the camera starts
+    if let Some(home) = bodies
+        .iter_mut()
+        .find(|body| body.parent.is_none() && matches!(body.material, MaterialDefinition::Rocky { .. }))
+    {
+        home.home = true;
+    }
+
+    StarSystemDefinition {
+        name: format!("Seed {} ({}-class)", seed, class.letter()),
+        star,
+        bodies,
+    }
+}
+
+/// Replaces the active star system with a generated one
+pub fn activate_generated_system(
+    commands: &mut Commands,
+    definitions: &mut Assets<StarSystemDefinition>,
+    seed: u64,
+) {
+    info!("Generated star system from seed {}", seed);
+    let handle = definitions.add(generate_star_system(seed));
+    commands.insert_resource(ActiveStarSystem(handle));
+}
+
+/// System that generates a fresh random system with F5
+/// The seed is logged so interesting systems can be shared
+pub fn regenerate_star_system(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut definitions: ResMut<Assets<StarSystemDefinition>>,
+) {
+    if key_input.just_pressed(KeyCode::F5) {
+        let seed = rand::thread_rng().r#gen::<u64>();
+        activate_generated_system(&mut commands, &mut definitions, seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Everything but the name, which contains the seed itself
+    fn to_ron(definition: &StarSystemDefinition) -> String {
+        ron::to_string(&(&definition.star, &definition.bodies)).unwrap()
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_system() {
+        for seed in [0, 1, 42, u64::MAX] {
+            assert_eq!(to_ron(&generate_star_system(seed)), to_ron(&generate_star_system(seed)));
+        }
+    }
+
+    #[test]
+    fn different_seeds_give_different_systems() {
+        let systems: Vec<String> = (0..8).map(|seed| to_ron(&generate_star_system(seed))).collect();
+        for (i, a) in systems.iter().enumerate() {
+            for b in &systems[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+}
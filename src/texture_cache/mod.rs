@@ -0,0 +1,271 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureViewDescriptor, TextureViewDimension},
+};
+use serde::Serialize;
+
+/// Environment variable overriding where generated textures are cached
+pub const CACHE_DIR_ENV_VAR: &str = "TEXTURE_CACHE_DIR";
+
+/// Default cache location, relative to the working directory
+const DEFAULT_CACHE_DIR: &str = ".cache/textures";
+
+/// Identifies cache files; bump when the file layout changes
+const CACHE_MAGIC: &[u8; 4] = b"TXC1";
+
+/// Header size: magic, width, height, layers, format, cube flag
+const HEADER_LEN: usize = 4 + 4 * 3 + 2;
+
+/// Numbers temporary files, so concurrent writers never share one
+static NEXT_TEMPORARY: AtomicU64 = AtomicU64::new(0);
+
+/// Cache key for a generated texture
+/// Any change to the generator name, version or parameters produces a new key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureCacheKey {
+    hash: u64,
+}
+
+impl TextureCacheKey {
+    /// Builds a key from a generator name, its version and its parameters
+    /// Bump the version whenever the generator's output changes for the same parameters
+    /// Returns `None` if the parameters can't be serialized, since any fallback
+    /// text would give unrelated textures the same key
+    pub fn new(generator: &str, version: u32, params: &impl Serialize) -> Option<Self> {
+        // RON is a stable textual form for parameters that contain floats
+        let params = match ron::to_string(params) {
+            Ok(params) => params,
+            Err(error) => {
+                warn!("Not caching {} texture, its parameters can't be serialized: {}", generator, error);
+                return None;
+            }
+        };
+        let mut hash = fnv1a(FNV_OFFSET, generator.as_bytes());
+        hash = fnv1a(hash, &version.to_le_bytes());
+        hash = fnv1a(hash, params.as_bytes());
+        Some(Self { hash })
+    }
+
+    /// Hex form of the key, used for file names and manifests
+    pub fn id(&self) -> String {
+        format!("{:016x}", self.hash)
+    }
+
+    fn path(&self) -> PathBuf {
+        let dir = std::env::var(CACHE_DIR_ENV_VAR).unwrap_or_else(|_| DEFAULT_CACHE_DIR.to_string());
+        PathBuf::from(dir).join(format!("{}.tex", self.id()))
+    }
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// 64-bit FNV-1a, which unlike std's hasher is stable across builds
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// Loads a texture from the cache, or generates and caches it
+/// Without a key the texture is always generated and never cached
+/// Meant to run inside a background texture task, since both paths can be slow
+pub fn load_or_generate(key: Option<TextureCacheKey>, generate: impl FnOnce() -> Image) -> Image {
+    let Some(key) = key else {
+        return generate();
+    };
+    if let Some(image) = load(key) {
+        return image;
+    }
+    let image = generate();
+    store(key, &image);
+    image
+}
+
+/// Reads a cached texture, or `None` if it is missing or unreadable
+pub fn load(key: TextureCacheKey) -> Option<Image> {
+    let path = key.path();
+    let bytes = fs::read(&path).ok()?;
+    match decode(&bytes) {
+        Ok(image) => {
+            info!("Loaded cached texture {}", path.display());
+            Some(image)
+        }
+        Err(error) => {
+            warn!("Ignoring texture cache file {}: {}", path.display(), error);
+            None
+        }
+    }
+}
+
+/// Writes a texture to the cache
+/// Failures are logged and otherwise ignored; the texture just gets regenerated next time
+pub fn store(key: TextureCacheKey, image: &Image) {
+    let path = key.path();
+    let Some(bytes) = encode(image) else {
+        return;
+    };
+
+    // Write to a temporary file first so a crash never leaves a half-written entry;
+    // its name is unique to this write, so processes and tasks storing the same
+    // key at once don't interleave their bytes
+    let temporary = path.with_extension(format!(
+        "{}.{}.tmp",
+        std::process::id(),
+        NEXT_TEMPORARY.fetch_add(1, Ordering::Relaxed),
+    ));
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&temporary, &bytes))
+        .and_then(|_| fs::rename(&temporary, &path));
+    if let Err(error) = result {
+        let _ = fs::remove_file(&temporary);
+        warn!("Failed to cache texture at {}: {}", path.display(), error);
+    }
+}
+
+/// Serializes an image into the cache file format
+/// `None` if it has no data or a format the cache doesn't support
+fn encode(image: &Image) -> Option<Vec<u8>> {
+    let data = image.data.as_ref()?;
+    let Some(tag) = format_tag(image.texture_descriptor.format) else {
+        warn!("Not caching texture with unsupported format {:?}", image.texture_descriptor.format);
+        return None;
+    };
+    let size = image.texture_descriptor.size;
+    let cube = image
+        .texture_view_descriptor
+        .as_ref()
+        .is_some_and(|view| view.dimension == Some(TextureViewDimension::Cube));
+
+    let mut bytes = Vec::with_capacity(HEADER_LEN + data.len());
+    bytes.extend_from_slice(CACHE_MAGIC);
+    bytes.extend_from_slice(&size.width.to_le_bytes());
+    bytes.extend_from_slice(&size.height.to_le_bytes());
+    bytes.extend_from_slice(&size.depth_or_array_layers.to_le_bytes());
+    bytes.push(tag);
+    bytes.push(cube as u8);
+    bytes.extend_from_slice(data);
+    Some(bytes)
+}
+
+/// Parses an image from the cache file format
+fn decode(bytes: &[u8]) -> Result<Image, &'static str> {
+    if bytes.len() < HEADER_LEN || &bytes[0..4] != CACHE_MAGIC {
+        return Err("not a texture cache file");
+    }
+
+    let read_u32 = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+    let size = Extent3d {
+        width: read_u32(4),
+        height: read_u32(8),
+        depth_or_array_layers: read_u32(12),
+    };
+    let format = format_from_tag(bytes[16]).ok_or("unknown texture format")?;
+    let cube = bytes[17] != 0;
+
+    let data = bytes[HEADER_LEN..].to_vec();
+    let expected = size.width as usize
+        * size.height as usize
+        * size.depth_or_array_layers as usize
+        * format.block_copy_size(None).unwrap_or(0) as usize;
+    if data.len() != expected {
+        return Err("truncated");
+    }
+
+    let mut image = Image::new(size, TextureDimension::D2, data, format, Default::default());
+    if cube {
+        image.texture_view_descriptor = Some(TextureViewDescriptor {
+            dimension: Some(TextureViewDimension::Cube),
+            ..Default::default()
+        });
+    }
+    Ok(image)
+}
+
+/// Texture formats the cache knows how to store
+fn format_tag(format: TextureFormat) -> Option<u8> {
+    match format {
+        TextureFormat::Rgba8UnormSrgb => Some(0),
+        TextureFormat::Rgba8Unorm => Some(1),
+        TextureFormat::Rgba16Float => Some(2),
+        TextureFormat::Rgba32Float => Some(3),
+        _ => None,
+    }
+}
+
+fn format_from_tag(tag: u8) -> Option<TextureFormat> {
+    match tag {
+        0 => Some(TextureFormat::Rgba8UnormSrgb),
+        1 => Some(TextureFormat::Rgba8Unorm),
+        2 => Some(TextureFormat::Rgba16Float),
+        3 => Some(TextureFormat::Rgba32Float),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Params {
+        size: u32,
+        scale: f32,
+    }
+
+    #[test]
+    fn key_is_stable_for_equal_params() {
+        let a = TextureCacheKey::new("test", 1, &Params { size: 64, scale: 0.5 }).unwrap();
+        let b = TextureCacheKey::new("test", 1, &Params { size: 64, scale: 0.5 }).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn key_changes_with_generator_version_and_params() {
+        let key = |generator, version, scale| TextureCacheKey::new(generator, version, &Params { size: 64, scale }).unwrap();
+        let base = key("test", 1, 0.5);
+        assert_ne!(base, key("other", 1, 0.5));
+        assert_ne!(base, key("test", 2, 0.5));
+        assert_ne!(base, key("test", 1, 0.51));
+    }
+
+    #[test]
+    fn cache_format_round_trips() {
+        let size = Extent3d { width: 2, height: 12, depth_or_array_layers: 1 };
+        let data: Vec<u8> = (0..2 * 12 * 8).map(|i| i as u8).collect();
+        let mut image = Image::new(size, TextureDimension::D2, data.clone(), TextureFormat::Rgba16Float, Default::default());
+        image.texture_view_descriptor = Some(TextureViewDescriptor {
+            dimension: Some(TextureViewDimension::Cube),
+            ..Default::default()
+        });
+
+        let decoded = decode(&encode(&image).unwrap()).unwrap();
+        assert_eq!(decoded.texture_descriptor.size, size);
+        assert_eq!(decoded.texture_descriptor.format, TextureFormat::Rgba16Float);
+        assert_eq!(decoded.data, Some(data));
+        assert_eq!(
+            decoded.texture_view_descriptor.and_then(|view| view.dimension),
+            Some(TextureViewDimension::Cube),
+        );
+    }
+
+    #[test]
+    fn decode_rejects_bad_files() {
+        let size = Extent3d { width: 4, height: 4, depth_or_array_layers: 1 };
+        let image = Image::new_fill(size, TextureDimension::D2, &[1, 2, 3, 4], TextureFormat::Rgba8UnormSrgb, Default::default());
+        let bytes = encode(&image).unwrap();
+
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(decode(&bytes[..HEADER_LEN - 1]).is_err());
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] = b'X';
+        assert!(decode(&wrong_magic).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task, futures::check_ready},
+};
+
+/// Source of task generations; later tasks get higher numbers
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Procedural texture being generated on a background thread
+/// The target handle shows a placeholder until the task finishes
+#[derive(Component)]
+pub struct TextureTask {
+    target: Handle<Image>,
+    task: Task<Image>,
+    /// Orders tasks for the same target, so only the newest one is applied
+    generation: u64,
+}
+
+/// Progress of background texture generation, for loading screens
+#[derive(Resource, Default)]
+pub struct TextureGenerationProgress {
+    /// Textures still being generated
+    pub pending: usize,
+    /// Textures finished since generation last went idle
+    pub completed: usize,
+}
+
+impl TextureGenerationProgress {
+    /// Fraction of the current batch that is done, 1.0 when idle
+    pub fn fraction(&self) -> f32 {
+        let total = self.pending + self.completed;
+        if total == 0 {
+            1.0
+        } else {
+            self.completed as f32 / total as f32
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.pending == 0
+    }
+}
+
+/// Adds a placeholder image and starts generating the real one in the background
+/// The returned handle can be used right away; its image is swapped in place when ready
+pub fn spawn_texture_task(
+    commands: &mut Commands,
+    images: &mut Assets<Image>,
+    placeholder: Image,
+    generate: impl FnOnce() -> Image + Send + 'static,
+) -> Handle<Image> {
+    let target = images.add(placeholder);
+    regenerate_texture(commands, target.clone(), generate);
+    target
+}
+
+/// Starts regenerating an existing image in the background
+/// The current image stays visible until the new one replaces it; any older
+/// task still working on the same image is superseded and its result discarded
+pub fn regenerate_texture(
+    commands: &mut Commands,
+    target: Handle<Image>,
+    generate: impl FnOnce() -> Image + Send + 'static,
+) {
+    let generation = NEXT_GENERATION.fetch_add(1, Ordering::Relaxed);
+    let task = AsyncComputeTaskPool::get().spawn(async move { generate() });
+    commands.spawn(TextureTask { target, task, generation });
+}
+
+/// System that swaps finished textures in over their placeholders
+/// Tasks superseded by a newer one for the same image are dropped, so a slow
+/// stale result can never overwrite a fresher one. Dropping doesn't interrupt a
+/// generator that is already running; it finishes and its image goes unused.
+pub fn poll_texture_tasks(
+    mut commands: Commands,
+    mut tasks: Query<(Entity, &mut TextureTask)>,
+    mut images: ResMut<Assets<Image>>,
+    mut progress: ResMut<TextureGenerationProgress>,
+) {
+    let mut newest: HashMap<AssetId<Image>, u64> = HashMap::new();
+    for (_, texture_task) in tasks.iter() {
+        let generation = newest.entry(texture_task.target.id()).or_default();
+        *generation = (*generation).max(texture_task.generation);
+    }
+
+    let mut pending = 0;
+    for (entity, mut texture_task) in tasks.iter_mut() {
+        if newest[&texture_task.target.id()] != texture_task.generation {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let Some(image) = check_ready(&mut texture_task.task) else {
+            pending += 1;
+            continue;
+        };
+
+        // Materials and skyboxes holding the handle pick up the new image automatically
+        if let Err(error) = images.insert(texture_task.target.id(), image) {
+            warn!("Dropping generated texture: {}", error);
+        }
+        commands.entity(entity).despawn();
+        progress.completed += 1;
+    }
+
+    progress.pending = pending;
+    if pending == 0 && progress.completed > 0 {
+        info!("Generated {} textures in the background", progress.completed);
+        progress.completed = 0;
+    }
+}